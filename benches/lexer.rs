@@ -0,0 +1,77 @@
+//! Measures the lexer throughput on a generated multi-megabyte FTL file.
+//!
+//! Run with `cargo bench --bench lexer`. Pass `-- --save-baseline` to store the result, later runs then report their
+//! speedup against it. To compare two revisions, save the baseline on the first one and run again on the second one.
+
+use std::{env, fs, hint::black_box, sync::Arc, time::Instant};
+
+use fortytwolang::{lexer::Lexer, source::Source};
+
+/// Number of generated functions. Results in a source file of roughly 8 MB.
+const FUNCTIONS: usize = 20_000;
+/// How often the source file is tokenized. The fastest run is reported.
+const RUNS: usize = 5;
+/// File storing the fastest run in milliseconds when the benchmark is run with `--save-baseline`.
+const BASELINE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/target/lexer-baseline");
+
+fn main() {
+	let source = Arc::new(Source::new("generated.ftl".to_owned(), generate_source()));
+	let megabytes = source.text.len() as f64 / 1_000_000.0;
+
+	let mut fastest = f64::INFINITY;
+	let mut token_count = 0;
+	for _ in 0..RUNS {
+		let start = Instant::now();
+		let (tokens, errors) = Lexer::new(&source).tokenize();
+		fastest = fastest.min(start.elapsed().as_secs_f64());
+		assert!(errors.is_empty());
+		token_count = black_box(tokens).len();
+	}
+	println!(
+		"lexed {:.1} MB into {} tokens in {:.1} ms ({:.1} MB/s)",
+		megabytes,
+		token_count,
+		fastest * 1000.0,
+		megabytes / fastest
+	);
+
+	let milliseconds = fastest * 1000.0;
+	if env::args().any(|argument| argument == "--save-baseline") {
+		fs::write(BASELINE, milliseconds.to_string()).expect("baseline can be written");
+		println!("saved baseline to {BASELINE}");
+	} else if let Some(baseline) = fs::read_to_string(BASELINE).ok().and_then(|baseline| baseline.parse::<f64>().ok()) {
+		println!("baseline {baseline:.1} ms, speedup {:.2}x", baseline / milliseconds);
+	} else {
+		println!("no baseline saved, run with `-- --save-baseline` to store one");
+	}
+}
+
+/// Generates FTL source code covering identifiers, keywords, numbers, literals, operators and comments.
+fn generate_source() -> String {
+	let mut source = String::new();
+	for i in 0..FUNCTIONS {
+		source.push_str(&format!(
+			r#"# Computes something for the {i}. time
+# with a comment spanning multiple lines
+struct Point{i} {{
+	x: int
+	y: float
+}}
+
+def compute_{i}(größe: int, factor: float): int {{
+	var counter: int = 0x{i:x} + 0b1010 * 1_000
+	var scaled: float = factor * 1.5e-3 + 2.0f64
+	while counter < größe and not (counter == 42) {{
+		counter = counter + {i}u32
+	}}
+	if counter >= 10 {{
+		print("value: \"{i}\"\n", 'x')
+	}}
+	return counter
+}}
+
+"#
+		));
+	}
+	source
+}
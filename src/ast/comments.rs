@@ -0,0 +1,43 @@
+use std::ops::Deref;
+
+use crate::source::PositionContainer;
+
+/// The text of a `#` comment, without the leading `#`.
+pub type Comment = PositionContainer<String>;
+
+/// Comments around an AST node, kept so that tools like the formatter do not lose them.
+#[derive(Debug, Default, Eq, PartialEq, Hash, Clone)]
+pub struct Comments {
+	/// Comment lines directly before the node.
+	pub leading: Vec<Comment>,
+	/// Comments after the node on the same line. For the last argument in a list, this also contains the comments
+	/// before the closing parenthesis.
+	pub trailing: Vec<Comment>,
+	/// Comment lines after the last field of a struct or the last top level node, which have no node to lead.
+	pub following: Vec<Comment>,
+}
+
+/// An AST node together with its [`Comments`].
+///
+/// Dereferences to the wrapped node, like [`PositionContainer`] does.
+#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+pub struct Commented<T> {
+	/// The wrapped node.
+	pub node: T,
+	/// Comments around the [node](Self::node).
+	pub comments: Comments,
+}
+
+impl<T> Commented<T> {
+	pub fn new(node: T, comments: Comments) -> Self {
+		Self { node, comments }
+	}
+}
+
+impl<T> Deref for Commented<T> {
+	type Target = T;
+
+	fn deref(&self) -> &Self::Target {
+		&self.node
+	}
+}
@@ -0,0 +1,23 @@
+use crate::{
+	ast::statement::DataType,
+	source::{PositionContainer, SourcePositionRange},
+};
+
+/// Allocation of a zero-initialized value on the heap like `alloc Person`, which produces a pointer to the value.
+///
+/// The value needs to be freed with `del`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Alloc {
+	/// The position of the `alloc` keyword.
+	pub position: SourcePositionRange,
+	/// The type of the allocated value, like `Person` in `alloc Person`.
+	pub data_type: PositionContainer<DataType>,
+}
+
+impl Alloc {
+	pub fn source_position(&self) -> SourcePositionRange {
+		let mut position = self.position.clone();
+		position.span.end = self.data_type.position.span.end;
+		position
+	}
+}
@@ -1,6 +1,6 @@
 use super::BinaryOperator;
 use crate::{
-	ast::Expression,
+	ast::{Comment, Expression},
 	source::{PositionContainer, SourcePositionRange},
 };
 
@@ -11,6 +11,8 @@ pub struct BinaryExpression {
 	pub lhs: Box<Expression>,
 	/// The operator connecting `lhs` and `rhs`.
 	pub operator: PositionContainer<BinaryOperator>,
+	/// Comments between the operator and `rhs`, e.g. at the end of a line broken after the operator.
+	pub comments: Vec<Comment>,
 	/// The right hand side.
 	pub rhs: Box<Expression>,
 }
@@ -18,7 +20,7 @@ pub struct BinaryExpression {
 impl BinaryExpression {
 	pub fn source_position(&self) -> SourcePositionRange {
 		let mut position = self.lhs.source_position();
-		position.position.end = self.rhs.source_position().position.end;
+		position.span.end = self.rhs.source_position().span.end;
 		position
 	}
 }
@@ -1,14 +1,18 @@
-use std::{cmp::Ordering, collections::HashMap};
+use std::fmt;
 
-// TODO: Implement Copy for BinaryOperator? See parser::Parser::parse_binary_operation_rhs() at `If the next binary
-//  operator binds stronger with rhs than with current, let it go with rhs`
 /// A binary operator connecting a lhs and a rhs.
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+///
+/// How strong an operator binds is defined by its [precedence](Self::precedence).
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub enum BinaryOperator {
 	/// Comparison if lhs is smaller/less than rhs (`<`).
 	Less,
 	/// Comparison if lhs is bigger/greater than rhs (`>`).
 	Greater,
+	/// Comparison if lhs is smaller/less than or equal to rhs (`<=`).
+	LessEqual,
+	/// Comparison if lhs is bigger/greater than or equal to rhs (`>=`).
+	GreaterEqual,
 	/// Addition (`+`).
 	Add,
 	/// Subtraction (`-`).
@@ -17,25 +21,120 @@ pub enum BinaryOperator {
 	Multiply,
 	/// Division (`/`)
 	Divide,
+	/// Remainder of the division (`mod` or `%`). Only for integers.
+	Modulus,
+	/// Bitwise OR (`bitor`). Only for integers.
+	BitOr,
+	/// Bitwise AND (`bitand`). Only for integers.
+	BitAnd,
+	/// Bitwise XOR (`bitxor`). Only for integers.
+	BitXor,
+	/// Shift of the lhs by rhs bits to the left (`shl`). Only for integers.
+	ShiftLeft,
+	/// Shift of the lhs by rhs bits to the right (`shr`). Only for integers.
+	ShiftRight,
+	/// Comparison if lhs equals rhs (`==`).
 	Equal,
+	/// Comparison if lhs does not equal rhs (`=/=`).
 	NotEqual,
+	/// Logical AND (`and`). The rhs is only evaluated if the lhs is true.
+	And,
+	/// Logical OR (`or`). The rhs is only evaluated if the lhs is false.
+	Or,
+	/// Logical XOR (`xor`).
+	Xor,
 }
 
-impl PartialOrd for BinaryOperator {
-	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-		// Precedence is a number indicating which precedence a token has over others. A higher precedence means that
-		// this BinaryOperator is preferred over others with less precedence.
-		// TODO: Use a 'lazy_static HashMap' or 'phf map' here
-		let mut precedence = HashMap::new();
-		precedence.insert(BinaryOperator::Less, 10);
-		precedence.insert(BinaryOperator::Greater, 10);
-		precedence.insert(BinaryOperator::Add, 20);
-		precedence.insert(BinaryOperator::Subtract, 20);
-		precedence.insert(BinaryOperator::Multiply, 30);
-		precedence.insert(BinaryOperator::Divide, 30);
-		precedence.insert(BinaryOperator::Equal, 5);
-		precedence.insert(BinaryOperator::NotEqual, 5);
+impl BinaryOperator {
+	/// Returns how strong this operator binds compared to others.
+	///
+	/// A higher precedence means that the operator binds stronger, e.g. `a + b * c` is `a + (b * c)`. All binary
+	/// operators are left associative, i.e. `a - b - c` is `(a - b) - c`.
+	pub fn precedence(&self) -> u8 {
+		match self {
+			BinaryOperator::Or => 1,
+			BinaryOperator::Xor => 2,
+			BinaryOperator::And => 3,
+			BinaryOperator::Equal | BinaryOperator::NotEqual => 4,
+			BinaryOperator::Less
+			| BinaryOperator::Greater
+			| BinaryOperator::LessEqual
+			| BinaryOperator::GreaterEqual => 5,
+			BinaryOperator::BitOr => 6,
+			BinaryOperator::BitXor => 7,
+			BinaryOperator::BitAnd => 8,
+			BinaryOperator::ShiftLeft | BinaryOperator::ShiftRight => 9,
+			BinaryOperator::Add | BinaryOperator::Subtract => 10,
+			BinaryOperator::Multiply | BinaryOperator::Divide | BinaryOperator::Modulus => 11,
+		}
+	}
+
+	/// Returns the left and right binding power of this operator, as used by the Pratt parser.
+	///
+	/// An operand between two operators belongs to the operator with the higher binding power on its side. The right
+	/// binding power is slightly higher than the left one, so that operators of the same precedence group from the
+	/// left.
+	pub fn binding_power(&self) -> (u8, u8) {
+		let precedence = self.precedence() * 2;
+		(precedence, precedence + 1)
+	}
+
+	/// Checks whether this operator compares its operands, i.e. produces a `bool` from two values of the same type.
+	pub fn is_comparison(&self) -> bool {
+		matches!(
+			self,
+			BinaryOperator::Less
+				| BinaryOperator::Greater
+				| BinaryOperator::LessEqual
+				| BinaryOperator::GreaterEqual
+				| BinaryOperator::Equal
+				| BinaryOperator::NotEqual
+		)
+	}
+
+	/// Checks whether this operator connects two `bool`s.
+	pub fn is_logical(&self) -> bool {
+		matches!(self, BinaryOperator::And | BinaryOperator::Or | BinaryOperator::Xor)
+	}
+
+	/// Checks whether this operator can only be applied to integers, i.e. is a bitwise operator or the modulus.
+	pub fn is_integer_only(&self) -> bool {
+		matches!(
+			self,
+			BinaryOperator::Modulus
+				| BinaryOperator::BitOr
+				| BinaryOperator::BitAnd
+				| BinaryOperator::BitXor
+				| BinaryOperator::ShiftLeft
+				| BinaryOperator::ShiftRight
+		)
+	}
+}
 
-		precedence[self].partial_cmp(&precedence[other])
+impl fmt::Display for BinaryOperator {
+	/// Formats the operator as written in FTL source code.
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let operator = match self {
+			BinaryOperator::Add => "+",
+			BinaryOperator::Subtract => "-",
+			BinaryOperator::Multiply => "*",
+			BinaryOperator::Divide => "/",
+			BinaryOperator::Modulus => "mod",
+			BinaryOperator::BitOr => "bitor",
+			BinaryOperator::BitAnd => "bitand",
+			BinaryOperator::BitXor => "bitxor",
+			BinaryOperator::ShiftLeft => "shl",
+			BinaryOperator::ShiftRight => "shr",
+			BinaryOperator::Less => "<",
+			BinaryOperator::Greater => ">",
+			BinaryOperator::LessEqual => "<=",
+			BinaryOperator::GreaterEqual => ">=",
+			BinaryOperator::Equal => "==",
+			BinaryOperator::NotEqual => "=/=",
+			BinaryOperator::And => "and",
+			BinaryOperator::Or => "or",
+			BinaryOperator::Xor => "xor",
+		};
+		write!(f, "{}", operator)
 	}
 }
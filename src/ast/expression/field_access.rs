@@ -0,0 +1,23 @@
+use crate::{
+	ast::Expression,
+	source::{PositionContainer, SourcePositionRange},
+};
+
+/// Access of a struct field like `p.age`.
+///
+/// If `base` is a pointer to a struct, the field of the pointed-to struct is accessed.
+#[derive(Debug, PartialEq, Clone)]
+pub struct FieldAccess {
+	/// The struct whose field is accessed, like `p` in `p.age`.
+	pub base: Box<Expression>,
+	/// The name of the accessed field, like `age` in `p.age`.
+	pub field: PositionContainer<String>,
+}
+
+impl FieldAccess {
+	pub fn source_position(&self) -> SourcePositionRange {
+		let mut position = self.base.source_position();
+		position.span.end = self.field.position.span.end;
+		position
+	}
+}
@@ -1,7 +1,10 @@
 use std::{fmt, ops::Deref};
 
 use super::Expression;
-use crate::source::PositionContainer;
+use crate::{
+	ast::{Comment, Commented},
+	source::PositionContainer,
+};
 
 /// A function call, i.e. the execution of a [`FunctionDefinition`](crate::ast::FunctionDefinition) with concrete parameters.
 #[derive(Debug, PartialEq, Clone)]
@@ -9,7 +12,9 @@ pub struct FunctionCall {
 	/// The name of the function to be called.
 	pub name: PositionContainer<String>,
 	/// The parameters to invoke the called function with.
-	pub params: Vec<Expression>,
+	pub params: Vec<Commented<Expression>>,
+	/// Comments inside the parentheses if there are no parameters, which would hold them otherwise.
+	pub comments: Vec<Comment>,
 }
 
 impl fmt::Display for FunctionCall {
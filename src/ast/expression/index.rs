@@ -0,0 +1,18 @@
+use crate::{ast::Expression, source::SourcePositionRange};
+
+/// Access of an array element like `numbers@3`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Index {
+	/// The array whose element is accessed, like `numbers` in `numbers@3`.
+	pub base: Box<Expression>,
+	/// The index of the accessed element, starting at `0`, like `3` in `numbers@3`.
+	pub index: Box<Expression>,
+}
+
+impl Index {
+	pub fn source_position(&self) -> SourcePositionRange {
+		let mut position = self.base.source_position();
+		position.span.end = self.index.source_position().span.end;
+		position
+	}
+}
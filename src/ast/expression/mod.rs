@@ -1,12 +1,29 @@
+mod alloc;
 mod binary_expression;
 mod binary_operator;
+mod field_access;
 mod function_call;
+mod index;
+mod string_literal;
+mod struct_literal;
+mod unary_expression;
+mod unary_operator;
 
+pub use alloc::Alloc;
 pub use binary_expression::BinaryExpression;
 pub use binary_operator::BinaryOperator;
+pub use field_access::FieldAccess;
 pub use function_call::FunctionCall;
+pub use index::Index;
+pub use string_literal::{StringLiteral, StringPart};
+pub use struct_literal::{FieldInitializer, StructLiteral};
+pub use unary_expression::UnaryExpression;
+pub use unary_operator::UnaryOperator;
 
-use crate::source::{PositionContainer, SourcePositionRange};
+use crate::{
+	source::{PositionContainer, SourcePositionRange},
+	token::NumberSuffix,
+};
 
 pub type Variable = PositionContainer<String>;
 
@@ -14,26 +31,66 @@ pub type Variable = PositionContainer<String>;
 #[derive(Debug, PartialEq, Clone)]
 pub enum Expression {
 	BinaryExpression(BinaryExpression),
+	Unary(UnaryExpression),
 	FunctionCall(FunctionCall),
+	FieldAccess(FieldAccess),
+	Index(Index),
+	StructLiteral(StructLiteral),
+	Alloc(Alloc),
+	String(StringLiteral),
 	Number(Number),
 	Variable(PositionContainer<String>),
+	/// The null pointer `nil`, which is a value of every pointer type.
+	Nil(SourcePositionRange),
 }
 
 impl Expression {
 	pub fn source_position(&self) -> SourcePositionRange {
 		match self {
 			Expression::BinaryExpression(binary_expression) => binary_expression.source_position(),
+			Expression::Unary(unary_expression) => unary_expression.source_position(),
 			Expression::FunctionCall(function_call) => function_call.name.position.clone(),
+			Expression::FieldAccess(field_access) => field_access.source_position(),
+			Expression::Index(index) => index.source_position(),
+			Expression::StructLiteral(struct_literal) => struct_literal.name.position.clone(),
+			Expression::Alloc(alloc) => alloc.source_position(),
+			Expression::String(string_literal) => string_literal.position.clone(),
 			Expression::Number(number) => number.position.clone(),
 			Expression::Variable(variable) => variable.position.clone(),
+			Expression::Nil(position) => position.clone(),
+		}
+	}
+
+	/// Checks whether the type of this expression depends on where it is used, which is the case for `nil`, (negated)
+	/// number literals without type suffix and arithmetic or bitwise expressions with only such operands.
+	pub fn is_untyped(&self) -> bool {
+		match self {
+			Expression::BinaryExpression(binary_expression) => {
+				let operator = &binary_expression.operator;
+				!operator.is_comparison()
+					&& !operator.is_logical()
+					&& binary_expression.lhs.is_untyped()
+					&& binary_expression.rhs.is_untyped()
+			},
+			Expression::Nil(_) => true,
+			Expression::Number(number) => {
+				matches!(number.value, NumberKind::Int(_, None) | NumberKind::Float(_, None))
+			},
+			Expression::Unary(unary_expression) => {
+				*unary_expression.operator == UnaryOperator::Negate
+					&& matches!(*unary_expression.operand, Expression::Number(_))
+					&& unary_expression.operand.is_untyped()
+			},
+			_ => false,
 		}
 	}
 }
 
 pub type Number = PositionContainer<NumberKind>;
 
+/// A number literal with its optional type suffix, like `42` or `1.5f32`.
 #[derive(Debug, PartialEq, Clone)]
 pub enum NumberKind {
-	Int(i64),
-	Float(f64),
+	Int(u64, Option<NumberSuffix>),
+	Float(f64, Option<NumberSuffix>),
 }
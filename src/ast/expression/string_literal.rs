@@ -0,0 +1,19 @@
+use crate::{ast::Expression, source::SourcePositionRange};
+
+/// A string literal like `"{name} is {age}"`, whose names enclosed in braces are interpolated.
+#[derive(Debug, PartialEq, Clone)]
+pub struct StringLiteral {
+	/// The position of the whole literal including its quotes.
+	pub position: SourcePositionRange,
+	/// The texts and interpolated values, in the order in which they are concatenated.
+	pub parts: Vec<StringPart>,
+}
+
+/// A part of a [`StringLiteral`].
+#[derive(Debug, PartialEq, Clone)]
+pub enum StringPart {
+	/// Text with resolved escape sequences, in which `{{` and `}}` became single braces.
+	Text(String),
+	/// A value converted to a string, like `{name}`.
+	Interpolation(Expression),
+}
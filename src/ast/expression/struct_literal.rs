@@ -0,0 +1,24 @@
+use crate::{
+	ast::{Comment, Commented, Expression},
+	source::PositionContainer,
+};
+
+/// Construction of a struct value with named field initializers, like `new Person(name="Linus", age=19)`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct StructLiteral {
+	/// The name of the constructed struct.
+	pub name: PositionContainer<String>,
+	/// The initial values of the fields. Each field of the struct is initialized exactly once.
+	pub fields: Vec<Commented<FieldInitializer>>,
+	/// Comments inside the parentheses if there are no field initializers, which would hold them otherwise.
+	pub comments: Vec<Comment>,
+}
+
+/// The initial value of a field in a [`StructLiteral`], like `age=19`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct FieldInitializer {
+	/// The name of the initialized field.
+	pub name: PositionContainer<String>,
+	/// The initial value of the field.
+	pub value: Expression,
+}
@@ -0,0 +1,24 @@
+use super::UnaryOperator;
+use crate::{
+	ast::{Comment, Expression},
+	source::{PositionContainer, SourcePositionRange},
+};
+
+/// A unary expression of the form `op operand` like `not done`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct UnaryExpression {
+	/// The operator applied to `operand`.
+	pub operator: PositionContainer<UnaryOperator>,
+	/// Comments between the operator and `operand`.
+	pub comments: Vec<Comment>,
+	/// The operand.
+	pub operand: Box<Expression>,
+}
+
+impl UnaryExpression {
+	pub fn source_position(&self) -> SourcePositionRange {
+		let mut position = self.operator.position.clone();
+		position.span.end = self.operand.source_position().span.end;
+		position
+	}
+}
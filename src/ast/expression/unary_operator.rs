@@ -0,0 +1,31 @@
+use std::fmt;
+
+/// A unary operator applied to a single operand.
+///
+/// Unary operators bind stronger than any [`BinaryOperator`](super::BinaryOperator), so `-a * b` is `(-a) * b`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum UnaryOperator {
+	/// Arithmetic negation (`-`).
+	Negate,
+	/// Logical NOT (`not`).
+	Not,
+	/// Bitwise complement (`bitnot`).
+	Complement,
+	/// Pointer to the operand (`ref`).
+	Reference,
+	/// Value the operand points to (`deref`).
+	Dereference,
+}
+
+impl fmt::Display for UnaryOperator {
+	/// Formats the operator as written in FTL source code.
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			UnaryOperator::Negate => write!(f, "-"),
+			UnaryOperator::Not => write!(f, "not"),
+			UnaryOperator::Complement => write!(f, "bitnot"),
+			UnaryOperator::Reference => write!(f, "ref"),
+			UnaryOperator::Dereference => write!(f, "deref"),
+		}
+	}
+}
@@ -0,0 +1,45 @@
+use super::Expression;
+use crate::{ast::Block, source::PositionContainer};
+
+/// Execute the `body` once *for* each value of the `iterable`, which is bound to the loop `variable`.
+///
+/// # Example
+///
+/// ```text
+/// for i in 0..10 step 2 {
+///     print(i)
+/// }
+/// ```
+///
+/// * The `variable` is `i`, which is only visible in the `body`.
+/// * The `iterable` is the [`Range`] from `0` up to, but excluding, `10` in steps of `2`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ForLoop {
+	/// Label like `outer` in `outer: for ...`, which `break` and `continue` can refer to.
+	pub label: Option<PositionContainer<String>>,
+	pub variable: PositionContainer<String>,
+	pub iterable: Iterable,
+	pub body: Block,
+}
+
+/// The values a [`ForLoop`] iterates over.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Iterable {
+	/// `for i in 0..n`: The integers of a range.
+	Range(Box<Range>),
+	/// `for x of array`: The elements of an array.
+	Array(Expression),
+}
+
+/// A range of integers like `0..n`, `0..=n` or `n..0 step -1`.
+///
+/// The range counts from `start` towards `end` in steps of `step`, which defaults to `1`. If `step` is negative,
+/// the range counts downwards.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Range {
+	pub start: Expression,
+	pub end: Expression,
+	/// Whether `end` is part of the range, i.e. `..=` instead of `..`.
+	pub inclusive: bool,
+	pub step: Option<Expression>,
+}
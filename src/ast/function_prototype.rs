@@ -1,15 +1,20 @@
 use crate::{
-	ast::{function_argument::FunctionArgument, statement::DataType},
+	ast::{function_argument::FunctionArgument, statement::DataType, Comment, Commented},
 	source::PositionContainer,
 };
 
 /// The header of the function i.e. function name and arguments, but not the body.
 #[derive(Debug, Eq, PartialEq, Hash, Clone)]
 pub struct FunctionPrototype {
+	/// The `##` doc comment before the function. For a [`FunctionDefinition`](crate::ast::FunctionDefinition), this is
+	/// the doc comment before the `def`.
+	pub doc: Option<Comment>,
 	/// The name of the function.
 	pub name: PositionContainer<String>,
 	/// The arguments for the function.
-	pub args: Vec<FunctionArgument>,
+	pub args: Vec<Commented<FunctionArgument>>,
+	/// Comments inside the parentheses if there are no arguments, which would hold them otherwise.
+	pub comments: Vec<Comment>,
 	/// Return type is what this function returns.
 	pub return_type: Option<PositionContainer<DataType>>,
 }
@@ -8,6 +8,8 @@ use crate::ast::Block;
 /// ```text
 /// if answer == 42 {
 ///     42
+/// } else if answer == 0 {
+///     1
 /// } else {
 ///     0
 /// }
@@ -15,10 +17,20 @@ use crate::ast::Block;
 ///
 /// * The `condition` is `answer == 42`.
 /// * The `if_true` expression is `42`.
+/// * The `else_ifs` contain the condition `answer == 0` with the expression `1`.
 /// * The `if_false` expression is `0´.
 #[derive(Debug, PartialEq, Clone)]
 pub struct IfElse {
 	pub condition: Expression,
 	pub if_true: Block,
+	/// The `else if` branches, whose conditions are checked in order if the `condition` is false.
+	pub else_ifs: Vec<ElseIf>,
 	pub if_false: Block,
 }
+
+/// An `else if` branch of an [`IfElse`], which is executed if its `condition` is the first true condition.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ElseIf {
+	pub condition: Expression,
+	pub body: Block,
+}
@@ -2,7 +2,9 @@
 //!
 //! The AST is a tree representation of the source code, which is used for [semantic analysis](crate::semantic_analyzer) and [code generation](crate::emitter).
 
+mod comments;
 pub mod expression;
+pub mod for_loop;
 mod function_argument;
 mod function_definition;
 mod function_prototype;
@@ -11,10 +13,12 @@ pub mod statement;
 pub mod struct_;
 mod while_loop;
 
+pub use comments::{Comment, Commented, Comments};
 pub use expression::Expression;
+pub use for_loop::ForLoop;
 pub use function_definition::FunctionDefinition;
 pub use function_prototype::FunctionPrototype;
-pub use if_else::IfElse;
+pub use if_else::{ElseIf, IfElse};
 pub use statement::Statement;
 pub use struct_::Struct;
 pub use while_loop::WhileLoop;
@@ -26,6 +30,7 @@ pub enum Instruction {
 	Statement(Statement),
 	IfElse(Box<IfElse>),
 	WhileLoop(Box<WhileLoop>),
+	ForLoop(Box<ForLoop>),
 }
 
 /// The top-level element of an AST.
@@ -36,5 +41,11 @@ pub enum Node {
 	Struct(Struct),
 }
 
-/// A list of instructions.
-pub type Block = Vec<Instruction>;
+/// A list of instructions enclosed by curly braces.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Block {
+	/// The instructions of the block.
+	pub instructions: Vec<Commented<Instruction>>,
+	/// Comments after the last instruction, e.g. in an otherwise empty block.
+	pub comments: Vec<Comment>,
+}
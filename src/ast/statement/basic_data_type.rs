@@ -1,12 +1,125 @@
-use std::fmt;
+use std::{fmt, ops::RangeInclusive};
 
-/// A basic data type is a type with hardware support like int and float.
+use crate::token::NumberSuffix;
+
+/// A basic data type is a type with hardware support like int and float, or a built-in type like str.
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub enum BasicDataType {
 	/// A integer number, like 42
 	Int,
+	/// A signed integer with 8 bits
+	Int8,
+	/// A signed integer with 16 bits
+	Int16,
+	/// A signed integer with 32 bits
+	Int32,
+	/// A signed integer with 64 bits
+	Int64,
+	/// An unsigned integer with 8 bits
+	UInt8,
+	/// An unsigned integer with 16 bits
+	UInt16,
+	/// An unsigned integer with 32 bits
+	UInt32,
+	/// An unsigned integer with 64 bits
+	UInt64,
 	/// A floating point number like 4.2
 	Float,
+	/// A floating point number with single precision
+	Float32,
+	/// A floating point number with double precision
+	Float64,
+	/// A truth value, i.e. the result of a comparison
+	Bool,
+	/// A single byte of a text, mainly to pass a `ptr char` to C functions
+	Char,
+	/// A text like "FTL", which consists of its length and a pointer to its UTF-8 bytes
+	Str,
+	/// No value, i.e. the return type of functions that do not return anything
+	Nothing,
+	/// A value of unknown type, which is only accessible through a pointer like `ptr any`
+	Any,
+}
+
+impl BasicDataType {
+	/// Checks whether this is a signed or unsigned integer type.
+	pub fn is_integer(&self) -> bool {
+		matches!(
+			self,
+			BasicDataType::Int
+				| BasicDataType::Int8
+				| BasicDataType::Int16
+				| BasicDataType::Int32
+				| BasicDataType::Int64
+				| BasicDataType::UInt8
+				| BasicDataType::UInt16
+				| BasicDataType::UInt32
+				| BasicDataType::UInt64
+		)
+	}
+
+	/// Checks whether this is an unsigned integer type.
+	pub fn is_unsigned(&self) -> bool {
+		matches!(self, BasicDataType::UInt8 | BasicDataType::UInt16 | BasicDataType::UInt32 | BasicDataType::UInt64)
+	}
+
+	/// Checks whether this is a floating point type.
+	pub fn is_float(&self) -> bool {
+		matches!(self, BasicDataType::Float | BasicDataType::Float32 | BasicDataType::Float64)
+	}
+
+	/// Returns the values of this integer type from the smallest to the biggest one, or `None` if this is no integer
+	/// type.
+	///
+	/// `int` is emitted as C `int` and therefore has the range of `int32`.
+	pub fn integer_range(&self) -> Option<RangeInclusive<i128>> {
+		let (min, max) = match self {
+			BasicDataType::Int8 => (i8::MIN.into(), i8::MAX.into()),
+			BasicDataType::Int16 => (i16::MIN.into(), i16::MAX.into()),
+			BasicDataType::Int | BasicDataType::Int32 => (i32::MIN.into(), i32::MAX.into()),
+			BasicDataType::Int64 => (i64::MIN.into(), i64::MAX.into()),
+			BasicDataType::UInt8 => (0, u8::MAX.into()),
+			BasicDataType::UInt16 => (0, u16::MAX.into()),
+			BasicDataType::UInt32 => (0, u32::MAX.into()),
+			BasicDataType::UInt64 => (0, u64::MAX.into()),
+			_ => return None,
+		};
+		Some(min..=max)
+	}
+
+	/// Returns the biggest finite value of this floating point type, or `None` if this is no floating point type.
+	///
+	/// `float` is emitted as C `float` and therefore has the range of `float32`.
+	pub fn float_max(&self) -> Option<f64> {
+		match self {
+			BasicDataType::Float | BasicDataType::Float32 => Some(f32::MAX.into()),
+			BasicDataType::Float64 => Some(f64::MAX),
+			_ => None,
+		}
+	}
+
+	/// Checks whether values of this type exist, i.e. whether it is neither `nothing` nor `any`.
+	pub fn has_values(&self) -> bool {
+		!matches!(self, BasicDataType::Nothing | BasicDataType::Any)
+	}
+}
+
+impl From<NumberSuffix> for BasicDataType {
+	/// Converts the suffix of a number literal like `42u8` to the type of the literal.
+	fn from(suffix: NumberSuffix) -> Self {
+		match suffix {
+			NumberSuffix::I8 => BasicDataType::Int8,
+			NumberSuffix::I16 => BasicDataType::Int16,
+			NumberSuffix::I32 => BasicDataType::Int32,
+			NumberSuffix::I64 => BasicDataType::Int64,
+			NumberSuffix::U8 => BasicDataType::UInt8,
+			NumberSuffix::U16 => BasicDataType::UInt16,
+			NumberSuffix::U32 => BasicDataType::UInt32,
+			NumberSuffix::U64 => BasicDataType::UInt64,
+			NumberSuffix::F32 => BasicDataType::Float32,
+			NumberSuffix::F64 => BasicDataType::Float64,
+		}
+	}
 }
 
 impl TryFrom<&str> for BasicDataType {
@@ -17,7 +130,22 @@ impl TryFrom<&str> for BasicDataType {
 	fn try_from(data_type: &str) -> Result<Self, Self::Error> {
 		match data_type {
 			"int" => Ok(BasicDataType::Int),
+			"int8" => Ok(BasicDataType::Int8),
+			"int16" => Ok(BasicDataType::Int16),
+			"int32" => Ok(BasicDataType::Int32),
+			"int64" => Ok(BasicDataType::Int64),
+			"uint8" => Ok(BasicDataType::UInt8),
+			"uint16" => Ok(BasicDataType::UInt16),
+			"uint32" => Ok(BasicDataType::UInt32),
+			"uint64" => Ok(BasicDataType::UInt64),
 			"float" => Ok(BasicDataType::Float),
+			"float32" => Ok(BasicDataType::Float32),
+			"float64" => Ok(BasicDataType::Float64),
+			"bool" => Ok(BasicDataType::Bool),
+			"char" => Ok(BasicDataType::Char),
+			"str" => Ok(BasicDataType::Str),
+			"nothing" => Ok(BasicDataType::Nothing),
+			"any" => Ok(BasicDataType::Any),
 			_ => Err(()), // No basic data type with this name
 		}
 	}
@@ -27,7 +155,22 @@ impl fmt::Display for BasicDataType {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 		match self {
 			BasicDataType::Int => write!(f, "int"),
+			BasicDataType::Int8 => write!(f, "int8"),
+			BasicDataType::Int16 => write!(f, "int16"),
+			BasicDataType::Int32 => write!(f, "int32"),
+			BasicDataType::Int64 => write!(f, "int64"),
+			BasicDataType::UInt8 => write!(f, "uint8"),
+			BasicDataType::UInt16 => write!(f, "uint16"),
+			BasicDataType::UInt32 => write!(f, "uint32"),
+			BasicDataType::UInt64 => write!(f, "uint64"),
 			BasicDataType::Float => write!(f, "float"),
+			BasicDataType::Float32 => write!(f, "float32"),
+			BasicDataType::Float64 => write!(f, "float64"),
+			BasicDataType::Bool => write!(f, "bool"),
+			BasicDataType::Char => write!(f, "char"),
+			BasicDataType::Str => write!(f, "str"),
+			BasicDataType::Nothing => write!(f, "nothing"),
+			BasicDataType::Any => write!(f, "any"),
 		}
 	}
 }
@@ -1,10 +1,16 @@
-use std::fmt;
+use std::{
+	fmt,
+	hash::{Hash, Hasher},
+};
 
 use super::basic_data_type::BasicDataType;
 use crate::source::PositionContainer;
 
-/// A data type is either basic, a struct, or a pointer to a data type.
-#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+/// A data type is either basic, a struct, a pointer to a data type or an array of a data type.
+///
+/// Two data types are equal if they describe the same type, regardless of where their inner types are written in the
+/// source code.
+#[derive(Debug, Clone)]
 pub enum DataType {
 	/// A basic data type like int and float.
 	Basic(BasicDataType),
@@ -12,6 +18,73 @@ pub enum DataType {
 	Struct(String),
 	/// A Pointer to a data type.
 	Pointer(Box<PositionContainer<DataType>>),
+	/// An array with a fixed number of elements, like `arr<int>(10)`.
+	Array { element: Box<PositionContainer<DataType>>, length: u64 },
+}
+
+impl DataType {
+	/// Checks whether this is an integer type.
+	pub fn is_integer(&self) -> bool {
+		matches!(self, DataType::Basic(basic) if basic.is_integer())
+	}
+
+	/// Checks whether this is an integer or floating point type.
+	pub fn is_numeric(&self) -> bool {
+		matches!(self, DataType::Basic(basic) if basic.is_integer() || basic.is_float())
+	}
+
+	/// Checks whether this is a pointer type.
+	pub fn is_pointer(&self) -> bool {
+		matches!(self, DataType::Pointer(_))
+	}
+
+	/// Checks whether this is the type `ptr any`, which can point to values of any type.
+	pub fn is_any_pointer(&self) -> bool {
+		matches!(self, DataType::Pointer(pointee) if pointee.value == DataType::Basic(BasicDataType::Any))
+	}
+
+	/// Checks whether values of this type can be stored, i.e. whether it does not contain `nothing` or `any` other
+	/// than behind a pointer.
+	pub fn has_values(&self) -> bool {
+		match self {
+			DataType::Basic(basic) => basic.has_values(),
+			DataType::Struct(_) | DataType::Pointer(_) => true,
+			DataType::Array { element, .. } => element.value.has_values(),
+		}
+	}
+}
+
+impl PartialEq for DataType {
+	fn eq(&self, other: &Self) -> bool {
+		match (self, other) {
+			(DataType::Basic(basic), DataType::Basic(other_basic)) => basic == other_basic,
+			(DataType::Struct(name), DataType::Struct(other_name)) => name == other_name,
+			(DataType::Pointer(pointee), DataType::Pointer(other_pointee)) => pointee.value == other_pointee.value,
+			(
+				DataType::Array { element, length },
+				DataType::Array { element: other_element, length: other_length },
+			) => element.value == other_element.value && length == other_length,
+			_ => false,
+		}
+	}
+}
+
+impl Eq for DataType {}
+
+impl Hash for DataType {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		// Like for equality, the positions of inner types are left out
+		std::mem::discriminant(self).hash(state);
+		match self {
+			DataType::Basic(basic) => basic.hash(state),
+			DataType::Struct(name) => name.hash(state),
+			DataType::Pointer(pointee) => pointee.value.hash(state),
+			DataType::Array { element, length } => {
+				element.value.hash(state);
+				length.hash(state);
+			},
+		}
+	}
 }
 
 impl fmt::Display for DataType {
@@ -20,6 +93,7 @@ impl fmt::Display for DataType {
 			DataType::Basic(basic_data_type) => write!(f, "{}", basic_data_type),
 			DataType::Struct(struct_name) => write!(f, "{}", struct_name),
 			DataType::Pointer(pointer) => write!(f, "ptr {}", pointer.value),
+			DataType::Array { element, length } => write!(f, "arr<{}>({})", element.value, length),
 		}
 	}
 }
@@ -0,0 +1,10 @@
+use crate::source::{PositionContainer, SourcePositionRange};
+
+/// A `break` or `continue`, which jumps out of or to the next iteration of the innermost loop, or of the loop with
+/// the given `label`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Jump {
+	/// Position of the `break` or `continue` keyword.
+	pub position: SourcePositionRange,
+	pub label: Option<PositionContainer<String>>,
+}
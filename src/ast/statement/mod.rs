@@ -1,9 +1,13 @@
 mod basic_data_type;
 mod data_type;
+mod jump;
+mod return_;
 mod var_assignment;
 
 pub use basic_data_type::BasicDataType;
 pub use data_type::DataType;
+pub use jump::Jump;
+pub use return_::Return;
 
 use super::Expression;
 pub use crate::ast::{
@@ -17,5 +21,9 @@ pub use crate::ast::{
 pub enum Statement {
 	VariableDeclaration(VariableDeclaration),
 	VariableAssignment(VariableAssignment),
-	Return(Expression),
+	Return(Return),
+	/// Frees the value a pointer points to, which was allocated with `alloc`.
+	Delete(Expression),
+	Break(Jump),
+	Continue(Jump),
 }
@@ -0,0 +1,10 @@
+use crate::{ast::Expression, source::SourcePositionRange};
+
+/// A `return` from the current function, with the returned value unless the function returns `nothing`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Return {
+	/// Position of the `return` keyword.
+	pub position: SourcePositionRange,
+	/// The returned value, which is left out in functions returning `nothing`.
+	pub value: Option<Expression>,
+}
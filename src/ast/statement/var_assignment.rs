@@ -7,11 +7,13 @@ use crate::{
 pub struct VariableDeclaration {
 	pub name: PositionContainer<String>,
 	pub data_type: PositionContainer<DataType>,
-	pub value: Expression,
+	/// The initial value. Without one, the variable is initialized with zeros.
+	pub value: Option<Expression>,
 }
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct VariableAssignment {
-	pub name: PositionContainer<String>,
+	/// The assigned variable, field or array element, like `x`, `p.age` or `numbers@3`.
+	pub target: Expression,
 	pub value: Expression,
 }
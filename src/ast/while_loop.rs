@@ -1,9 +1,11 @@
 use super::Expression;
-use crate::ast::Block;
+use crate::{ast::Block, source::PositionContainer};
 
 /// Execute the `body` *while* the `condition` is true.
 #[derive(Debug, PartialEq, Clone)]
 pub struct WhileLoop {
+	/// Label like `outer` in `outer: while ...`, which `break` and `continue` can refer to.
+	pub label: Option<PositionContainer<String>>,
 	pub condition: Expression,
 	pub body: Block,
 }
@@ -18,11 +18,33 @@ pub enum Command {
 	Compile {
 		/// The file to compile.
 		file: std::path::PathBuf,
+		/// Check array indexes at runtime.
+		#[clap(long)]
+		debug: bool,
 	},
 
 	/// Compile and execute.
 	Run {
 		/// The file to run.
 		file: std::path::PathBuf,
+		/// Check array indexes at runtime.
+		#[clap(long)]
+		debug: bool,
 	},
+
+	/// Generate API documentation from the `##` doc comments.
+	Doc {
+		/// The file to document.
+		file: std::path::PathBuf,
+		/// The format of the documentation, which is written to stdout.
+		#[clap(long, value_enum, default_value_t = DocFormat::Markdown)]
+		format: DocFormat,
+	},
+}
+
+/// Output format of the [`Command::Doc`] command.
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+pub enum DocFormat {
+	Markdown,
+	Html,
 }
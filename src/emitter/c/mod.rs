@@ -1,28 +1,47 @@
 //! C emitter.
 
-use std::io;
+use std::{io, sync::Arc};
 
 use crate::{
 	ast,
 	ast::{
-		expression::BinaryOperator,
+		expression::{BinaryOperator, StringPart, UnaryOperator},
+		for_loop::Iterable,
 		statement::{BasicDataType, DataType},
 		Expression,
 	},
+	semantic_analyzer::{SymbolTable, TypeChecker, Variable},
 	source::PositionContainer,
+	token::NumberSuffix,
 };
 
+/// C code emitted before the program, containing the includes and helper functions like bounds checks and strings.
+pub(super) const RUNTIME: &str = include_str!("runtime.c");
+
 /// Emits C code.
 pub struct Emitter {
 	writer: Box<dyn io::Write>,
+	/// Keeps track of the variables in scope to infer the types of expressions, e.g. to choose between `.` and `->`.
+	type_checker: TypeChecker,
+	/// FTL labels and the corresponding unique C labels of the loops enclosing the current instruction, from the
+	/// outermost to the innermost loop.
+	loop_labels: Vec<Option<(String, String)>>,
+	/// Number of labeled loops emitted so far, used to make their C labels unique.
+	label_count: usize,
 }
 
 impl super::Emitter for Emitter {
-	fn codegen(ast_nodes: impl Iterator<Item = ast::Node>, writer: Box<dyn io::Write>) -> io::Result<()> {
-		let mut this = Self { writer };
+	fn codegen(
+		ast_nodes: impl Iterator<Item = ast::Commented<ast::Node>>,
+		writer: Box<dyn io::Write>,
+	) -> io::Result<()> {
+		let ast_nodes: Vec<ast::Node> = ast_nodes.map(|ast_node| ast_node.node).collect();
+		let Ok(symbol_table) = SymbolTable::global_symbol_scan(ast_nodes.iter());
+		let mut this =
+			Self { writer, type_checker: TypeChecker::new(symbol_table), loop_labels: Vec::new(), label_count: 0 };
 
 		// Prelude
-		writeln!(this.writer, "#include <stdio.h>\n#include <stdlib.h>")?;
+		writeln!(this.writer, "{}", RUNTIME)?;
 
 		for ast_node in ast_nodes {
 			this.ast_node(ast_node)?;
@@ -38,7 +57,6 @@ impl Emitter {
 			ast::Node::Function(function) => self.function(function),
 			ast::Node::Struct(struct_) => self.struct_(struct_),
 			ast::Node::FunctionPrototype(_) => Ok(()), // extern function
-			_ => todo!(),
 		}
 	}
 
@@ -55,72 +73,262 @@ impl Emitter {
 		write!(self.writer, "{}(", *function.prototype.name)?;
 
 		// Function arguments
+		self.type_checker.push_call_stack_frame();
 		for (i, arg) in function.prototype.args.into_iter().enumerate() {
 			if i != 0 {
 				write!(self.writer, ", ")?;
 			}
-			self.function_argument(arg)?;
+			self.function_argument(arg.node)?;
 		}
 		writeln!(self.writer, ") {{")?;
 
 		// Function body
-		for instruction in function.body {
-			self.instruction(instruction)?;
-		}
+		self.block(function.body)?;
+		self.type_checker.drop_call_stack_frame();
 		writeln!(self.writer)?;
 		writeln!(self.writer, "}}")?;
 		Ok(())
 	}
 
+	/// Emits a struct, which is declared first so that its fields can point to the struct itself.
 	fn struct_(&mut self, struct_: ast::Struct) -> io::Result<()> {
-		writeln!(self.writer, "typedef struct {{",)?;
+		writeln!(self.writer, "typedef struct {0} {0};", *struct_.name)?;
+		writeln!(self.writer, "struct {} {{", *struct_.name)?;
 		for field in struct_.fields {
-			self.data_type(field.data_type)?;
-			write!(self.writer, " {};", *field.name)?;
+			let field = field.node;
+			self.declaration(field.data_type, &field.name)?;
+			write!(self.writer, ";")?;
 		}
-		writeln!(self.writer, "}} {};", *struct_.name)?;
+		writeln!(self.writer, "}};")?;
 		Ok(())
 	}
 
 	fn instruction(&mut self, instruction: ast::Instruction) -> io::Result<()> {
 		match instruction {
-			ast::Instruction::Expression(expression) => self.expression(expression),
+			ast::Instruction::Expression(expression) => {
+				self.expression(expression)?;
+				writeln!(self.writer, ";")
+			},
 			ast::Instruction::Statement(statement) => self.statement(statement),
 			ast::Instruction::IfElse(if_else) => self.if_else(*if_else),
 			ast::Instruction::WhileLoop(while_loop) => self.while_loop(*while_loop),
+			ast::Instruction::ForLoop(for_loop) => self.for_loop(*for_loop),
 		}
 	}
 
 	fn expression(&mut self, expression: ast::Expression) -> io::Result<()> {
 		match expression {
 			Expression::BinaryExpression(binary_expression) => self.binary_expression(binary_expression),
+			Expression::Unary(unary_expression) => self.unary_expression(unary_expression),
 			Expression::FunctionCall(function_call) => self.function_call(function_call),
-			Expression::Number(number) => self.number(number),
+			Expression::FieldAccess(field_access) => self.field_access(field_access),
+			Expression::StructLiteral(struct_literal) => self.struct_literal(struct_literal),
+			Expression::Index(index) => self.index(index),
+			Expression::Alloc(alloc) => self.alloc(alloc),
+			Expression::String(string_literal) => self.string_literal(string_literal),
+			Expression::Number(number) => self.number(number, false),
 			Expression::Variable(variable) => self.variable(variable),
+			Expression::Nil(_) => write!(self.writer, "NULL"),
+		}
+	}
+
+	/// Emits a field access with `->` if the base is a pointer, and with `.` otherwise.
+	fn field_access(&mut self, field_access: ast::expression::FieldAccess) -> io::Result<()> {
+		let operator = match self.expression_type(&field_access.base) {
+			DataType::Pointer(_) => "->",
+			_ => ".",
+		};
+		self.expression(*field_access.base)?;
+		write!(self.writer, "{}{}", operator, *field_access.field)
+	}
+
+	/// Emits an allocation as `calloc`, so that the allocated value is initialized with zeros.
+	fn alloc(&mut self, alloc: ast::expression::Alloc) -> io::Result<()> {
+		write!(self.writer, "calloc(1, sizeof(")?;
+		self.declaration(alloc.data_type, "")?;
+		write!(self.writer, "))")
+	}
+
+	/// Emits a string literal as concatenation of its texts and interpolated values, which are converted to strings
+	/// with `ftl_str_format`.
+	fn string_literal(&mut self, string_literal: ast::expression::StringLiteral) -> io::Result<()> {
+		if string_literal.parts.is_empty() {
+			return write!(self.writer, "{}", c_string(""));
+		}
+		// All but the last part are the left operand of a concatenation
+		for _ in 1..string_literal.parts.len() {
+			write!(self.writer, "ftl_str_concat(")?;
+		}
+		for (i, part) in string_literal.parts.into_iter().enumerate() {
+			if i != 0 {
+				write!(self.writer, ", ")?;
+			}
+			match part {
+				StringPart::Text(text) => write!(self.writer, "{}", c_string(&text))?,
+				StringPart::Interpolation(value) => self.interpolation(value)?,
+			}
+			if i != 0 {
+				write!(self.writer, ")")?;
+			}
 		}
+		Ok(())
 	}
 
+	/// Emits the conversion of a value, which is interpolated into a string, to a string.
+	fn interpolation(&mut self, value: Expression) -> io::Result<()> {
+		let basic_data_type = match self.expression_type(&value) {
+			DataType::Basic(basic_data_type) => basic_data_type,
+			_ => unreachable!("Interpolating non-basic types is rejected by the type checker"),
+		};
+		let (format, cast) = match basic_data_type {
+			BasicDataType::Str => return self.expression(value),
+			integer if integer.is_unsigned() => ("%llu", "(unsigned long long)"),
+			integer if integer.is_integer() => ("%lld", "(long long)"),
+			float if float.is_float() => ("%g", "(double)"),
+			BasicDataType::Char => ("%c", ""),
+			BasicDataType::Bool => {
+				write!(self.writer, "ftl_str_format(\"%s\", ")?;
+				self.expression(value)?;
+				return write!(self.writer, " ? \"true\" : \"false\")");
+			},
+			_ => unreachable!("Interpolating `nothing` and `any` is rejected by the type checker"),
+		};
+		write!(self.writer, "ftl_str_format(\"{}\", {}", format, cast)?;
+		self.expression(value)?;
+		write!(self.writer, ")")
+	}
+
+	/// Emits a struct literal as compound literal with designated initializers like `(Person){.age = 19}`.
+	/// Emits a compound literal of a struct. Fields that are left out, i.e. arrays, are zero-initialized by C.
+	fn struct_literal(&mut self, struct_literal: ast::expression::StructLiteral) -> io::Result<()> {
+		write!(self.writer, "({}){{", *struct_literal.name)?;
+		// An empty initializer list is no valid C before C23
+		if struct_literal.fields.is_empty() {
+			write!(self.writer, "0")?;
+		}
+		for (i, initializer) in struct_literal.fields.into_iter().enumerate() {
+			if i != 0 {
+				write!(self.writer, ", ")?;
+			}
+			write!(self.writer, ".{} = ", *initializer.node.name)?;
+			self.expression(initializer.node.value)?;
+		}
+		write!(self.writer, "}}")
+	}
+
+	/// Emits an index into a C array. The index is passed through `ftl_index` from the prelude to check it against
+	/// the length of the array in debug builds.
+	fn index(&mut self, index: ast::expression::Index) -> io::Result<()> {
+		let length = match self.expression_type(&index.base) {
+			DataType::Array { length, .. } => length,
+			_ => unreachable!("Indexing non-arrays is rejected by the type checker"),
+		};
+		self.expression(*index.base)?;
+		write!(self.writer, "[ftl_index(")?;
+		self.expression(*index.index)?;
+		write!(self.writer, ", {})]", length)
+	}
+
+	/// Returns the type of an expression, as inferred by the [`TypeChecker`].
+	fn expression_type(&self, expression: &Expression) -> DataType {
+		self.type_checker.infer_expression_type(expression).expect("Only type checked expressions are emitted")
+	}
+
+	/// Emits a binary expression enclosed in parentheses, so that the precedence of the FTL operators is kept
+	/// regardless of the precedence of the corresponding C operators.
 	fn binary_expression(&mut self, binary_expression: ast::expression::BinaryExpression) -> io::Result<()> {
+		// `nil` and number literals without suffix take the type of the other operand, which is then no string
+		if !binary_expression.lhs.is_untyped()
+			&& self.expression_type(&binary_expression.lhs) == DataType::Basic(BasicDataType::Str)
+		{
+			return self.string_binary_expression(binary_expression);
+		}
+		write!(self.writer, "(")?;
 		self.expression(*binary_expression.lhs)?;
 		let operator = match *binary_expression.operator {
 			ast::expression::BinaryOperator::Add => "+",
 			ast::expression::BinaryOperator::Subtract => "-",
 			ast::expression::BinaryOperator::Multiply => "*",
 			ast::expression::BinaryOperator::Divide => "/",
+			BinaryOperator::Modulus => "%",
+			BinaryOperator::BitOr => "|",
+			BinaryOperator::BitAnd => "&",
+			BinaryOperator::BitXor => "^",
+			BinaryOperator::ShiftLeft => "<<",
+			BinaryOperator::ShiftRight => ">>",
 			BinaryOperator::Less => "<",
 			BinaryOperator::Greater => ">",
+			BinaryOperator::LessEqual => "<=",
+			BinaryOperator::GreaterEqual => ">=",
 			BinaryOperator::Equal => "==",
-			BinaryOperator::NotEqual => "=/=",
+			BinaryOperator::NotEqual => "!=",
+			// `&&` and `||` short-circuit like `and` and `or`
+			BinaryOperator::And => "&&",
+			BinaryOperator::Or => "||",
+			// Both operands are `bool`s, for which inequality is exclusive or
+			BinaryOperator::Xor => "!=",
 		};
 		write!(self.writer, " {} ", operator)?;
 		self.expression(*binary_expression.rhs)?;
+		write!(self.writer, ")")?;
+		Ok(())
+	}
+
+	/// Emits the concatenation or comparison of strings with the functions of the runtime.
+	fn string_binary_expression(&mut self, binary_expression: ast::expression::BinaryExpression) -> io::Result<()> {
+		let comparison = match *binary_expression.operator {
+			BinaryOperator::Add => None,
+			BinaryOperator::Less => Some("<"),
+			BinaryOperator::Greater => Some(">"),
+			BinaryOperator::LessEqual => Some("<="),
+			BinaryOperator::GreaterEqual => Some(">="),
+			BinaryOperator::Equal => Some("=="),
+			BinaryOperator::NotEqual => Some("!="),
+			_ => unreachable!("Other operators on strings are rejected by the type checker"),
+		};
+		let function = if comparison.is_some() { "(ftl_str_compare" } else { "ftl_str_concat" };
+		write!(self.writer, "{}(", function)?;
+		self.expression(*binary_expression.lhs)?;
+		write!(self.writer, ", ")?;
+		self.expression(*binary_expression.rhs)?;
+		write!(self.writer, ")")?;
+		if let Some(comparison) = comparison {
+			write!(self.writer, " {} 0)", comparison)?;
+		}
+		Ok(())
+	}
+
+	/// Emits a unary expression enclosed in parentheses, so that e.g. `- -x` does not become the decrement `--x`.
+	fn unary_expression(&mut self, unary_expression: ast::expression::UnaryExpression) -> io::Result<()> {
+		let operand = match (&*unary_expression.operator, *unary_expression.operand) {
+			// A negated number is emitted as a whole, see `Self::number`
+			(UnaryOperator::Negate, Expression::Number(number)) => {
+				write!(self.writer, "(")?;
+				self.number(number, true)?;
+				return write!(self.writer, ")");
+			},
+			(_, operand) => operand,
+		};
+		let operator = match *unary_expression.operator {
+			UnaryOperator::Negate => "-",
+			UnaryOperator::Not => "!",
+			UnaryOperator::Complement => "~",
+			UnaryOperator::Reference => "&",
+			UnaryOperator::Dereference => "*",
+		};
+		write!(self.writer, "({}", operator)?;
+		self.expression(operand)?;
+		write!(self.writer, ")")?;
 		Ok(())
 	}
 
 	fn function_call(&mut self, function_call: ast::expression::FunctionCall) -> io::Result<()> {
 		write!(self.writer, "{}(", *function_call.name)?;
-		for param in function_call.params {
-			self.expression(param)?;
+		for (i, param) in function_call.params.into_iter().enumerate() {
+			if i != 0 {
+				write!(self.writer, ", ")?;
+			}
+			self.expression(param.node)?;
 		}
 		write!(self.writer, ")")?;
 		Ok(())
@@ -132,49 +340,94 @@ impl Emitter {
 				self.variable_declaration(variable_declaration)
 			},
 			ast::statement::Statement::VariableAssignment(assignment) => self.variable_assignment(assignment),
-			ast::statement::Statement::Return(expression) => self.return_(expression),
+			ast::statement::Statement::Return(return_) => self.return_(return_),
+			ast::statement::Statement::Delete(pointer) => self.delete(pointer),
+			ast::statement::Statement::Break(jump) => self.jump("break", jump),
+			ast::statement::Statement::Continue(jump) => self.jump("continue", jump),
 		}
 	}
 
 	fn variable_declaration(&mut self, variable_declaration: ast::statement::VariableDeclaration) -> io::Result<()> {
-		write!(self.writer, "{} {} = ", *variable_declaration.data_type, *variable_declaration.name)?;
-		self.expression(variable_declaration.value)?;
+		self.type_checker.add_variable(Arc::new(Variable {
+			name: variable_declaration.name.clone(),
+			type_: variable_declaration.data_type.value.clone(),
+		}));
+		self.declaration(variable_declaration.data_type, &variable_declaration.name)?;
+		match variable_declaration.value {
+			Some(value) => {
+				write!(self.writer, " = ")?;
+				self.expression(value)?;
+			},
+			// Zero-initializes variables of any type, including structs and arrays
+			None => write!(self.writer, " = {{0}}")?,
+		}
 		writeln!(self.writer, ";")?;
 		Ok(())
 	}
 
 	fn variable_assignment(&mut self, assignment: ast::statement::VariableAssignment) -> io::Result<()> {
-		write!(self.writer, "{} = ", *assignment.name)?;
+		self.expression(assignment.target)?;
+		write!(self.writer, " = ")?;
 		self.expression(assignment.value)?;
 		writeln!(self.writer, ";")?;
 		Ok(())
 	}
 
-	fn return_(&mut self, expression: ast::Expression) -> io::Result<()> {
-		write!(self.writer, "return ")?;
-		self.expression(expression)?;
+	fn return_(&mut self, return_: ast::statement::Return) -> io::Result<()> {
+		write!(self.writer, "return")?;
+		if let Some(value) = return_.value {
+			write!(self.writer, " ")?;
+			self.expression(value)?;
+		}
 		writeln!(self.writer, ";")?;
 		Ok(())
 	}
 
+	fn delete(&mut self, pointer: ast::Expression) -> io::Result<()> {
+		write!(self.writer, "free(")?;
+		self.expression(pointer)?;
+		writeln!(self.writer, ");")
+	}
+
+	/// Emits a `break` or `continue`. Jumps to a labeled loop are emitted as `goto` to the labels emitted by
+	/// [`Self::loop_body`].
+	fn jump(&mut self, keyword: &str, jump: ast::statement::Jump) -> io::Result<()> {
+		let Some(label) = jump.label else {
+			return writeln!(self.writer, "{};", keyword);
+		};
+		let (_, c_label) = self
+			.loop_labels
+			.iter()
+			.rev()
+			.flatten()
+			.find(|(ftl_label, _)| *ftl_label == label.value)
+			.expect("Unknown labels are rejected by the type checker");
+		writeln!(self.writer, "goto ftl_{}_{};", keyword, c_label)
+	}
+
 	fn if_else(&mut self, if_else: ast::IfElse) -> io::Result<()> {
 		// if block, always present
 		write!(self.writer, "if (")?;
 		self.expression(if_else.condition)?;
 		writeln!(self.writer, ") {{")?;
-		for instruction in if_else.if_true {
-			self.instruction(instruction)?;
-		}
+		self.block(if_else.if_true)?;
 		writeln!(self.writer, "}}")?;
 
+		// else if blocks, optional
+		for else_if in if_else.else_ifs {
+			write!(self.writer, "else if (")?;
+			self.expression(else_if.condition)?;
+			writeln!(self.writer, ") {{")?;
+			self.block(else_if.body)?;
+			writeln!(self.writer, "}}")?;
+		}
+
 		// else block, optional
-		if if_else.if_false.is_empty() {
+		if if_else.if_false.instructions.is_empty() {
 			return Ok(());
 		}
 		writeln!(self.writer, "else {{")?;
-		for instruction in if_else.if_false {
-			self.instruction(instruction)?;
-		}
+		self.block(if_else.if_false)?;
 		writeln!(self.writer, "}}")?;
 
 		Ok(())
@@ -184,16 +437,155 @@ impl Emitter {
 		write!(self.writer, "while (")?;
 		self.expression(while_loop.condition)?;
 		writeln!(self.writer, ") {{")?;
-		for instruction in while_loop.body {
-			self.instruction(instruction)?;
+		self.loop_body(while_loop.label, while_loop.body)
+	}
+
+	fn for_loop(&mut self, for_loop: ast::ForLoop) -> io::Result<()> {
+		// Scope of the loop variable
+		self.type_checker.push_call_stack_frame();
+		match for_loop.iterable {
+			Iterable::Range(range) => self.range_loop_header(&for_loop.variable, *range)?,
+			Iterable::Array(array) => self.array_loop_header(&for_loop.variable, array)?,
+		}
+		self.loop_body(for_loop.label, for_loop.body)?;
+		self.type_checker.drop_call_stack_frame();
+		Ok(())
+	}
+
+	/// Emits the body of a loop up to its closing brace.
+	///
+	/// C's `break` and `continue` only refer to the innermost loop, so labeled loops get a C label at the end of their
+	/// body for `continue` and one after the loop for `break`, see [`Self::jump`]. Since C labels are visible in the
+	/// whole function, they are numbered to be unique.
+	fn loop_body(&mut self, label: Option<PositionContainer<String>>, body: ast::Block) -> io::Result<()> {
+		let label = label.map(|label| {
+			self.label_count += 1;
+			let c_label = format!("{}_{}", label.value, self.label_count);
+			(label.value, c_label)
+		});
+		self.loop_labels.push(label.clone());
+		self.block(body)?;
+		self.loop_labels.pop();
+		if let Some((_, c_label)) = &label {
+			writeln!(self.writer, "ftl_continue_{}: ;", c_label)?;
 		}
 		writeln!(self.writer, "}}")?;
+		if let Some((_, c_label)) = &label {
+			writeln!(self.writer, "ftl_break_{}: ;", c_label)?;
+		}
+		Ok(())
+	}
+
+	/// Emits the header of a C `for` loop over the integers of `range`, which are assigned to `variable`.
+	///
+	/// The end and step of the range are evaluated only once before the loop, and stored in helper variables, which
+	/// are prefixed with `ftl_` like the names of the runtime.
+	fn range_loop_header(
+		&mut self,
+		variable: &PositionContainer<String>,
+		range: ast::for_loop::Range,
+	) -> io::Result<()> {
+		let comparison = if range.inclusive { "=" } else { "" };
+		let variable_type = self.type_checker.infer_range_type(&range).expect("Only type checked ranges are emitted");
+		self.type_checker.add_variable(Arc::new(Variable { name: variable.clone(), type_: variable_type.clone() }));
+		let variable = variable.value.as_str();
+		write!(self.writer, "for (")?;
+		self.data_type(PositionContainer::new(variable_type, range.start.source_position()))?;
+		write!(self.writer, " {} = ", variable)?;
+		self.expression(range.start)?;
+		write!(self.writer, ", ftl_end_{} = ", variable)?;
+		self.expression(range.end)?;
+		match range.step {
+			None => writeln!(self.writer, "; {0} <{1} ftl_end_{0}; {0}++) {{", variable, comparison),
+			Some(step) => {
+				write!(self.writer, ", ftl_step_{} = ", variable)?;
+				self.expression(step)?;
+				// A negative step counts downwards
+				writeln!(
+					self.writer,
+					"; ftl_step_{0} > 0 ? {0} <{1} ftl_end_{0} : {0} >{1} ftl_end_{0}; {0} += ftl_step_{0}) {{",
+					variable, comparison
+				)
+			},
+		}
+	}
+
+	/// Emits the header of a C `for` loop over the indexes of `array`, followed by the declaration of `variable` as the
+	/// element at the current index.
+	///
+	/// C arrays cannot be copied by initialization, so if the elements are arrays themselves, `variable` is declared as
+	/// pointer to the first element of the inner array, like `int *row = (matrix)[ftl_index_row];`. Indexing `variable`
+	/// then works like indexing the inner array.
+	fn array_loop_header(&mut self, variable: &PositionContainer<String>, array: Expression) -> io::Result<()> {
+		let (element, length) = match self.expression_type(&array) {
+			DataType::Array { element, length } => (*element, length),
+			_ => unreachable!("Iterating over non-arrays is rejected by the type checker"),
+		};
+		self.type_checker.add_variable(Arc::new(Variable { name: variable.clone(), type_: element.value.clone() }));
+		let declared_type = match element.value {
+			DataType::Array { element: inner_element, .. } => {
+				PositionContainer::new(DataType::Pointer(inner_element), element.position)
+			},
+			_ => element,
+		};
+		let variable = variable.value.as_str();
+		writeln!(
+			self.writer,
+			"for (long long ftl_index_{0} = 0; ftl_index_{0} < {1}; ftl_index_{0}++) {{",
+			variable, length
+		)?;
+		self.declaration(declared_type, variable)?;
+		write!(self.writer, " = (")?;
+		self.expression(array)?;
+		writeln!(self.writer, ")[ftl_index_{}];", variable)
+	}
+
+	/// Emits the instructions of a block, whose variables are only in scope inside the block.
+	fn block(&mut self, block: ast::Block) -> io::Result<()> {
+		self.type_checker.push_call_stack_frame();
+		for instruction in block.instructions {
+			self.instruction(instruction.node)?;
+		}
+		self.type_checker.drop_call_stack_frame();
 		Ok(())
 	}
 
 	fn function_argument(&mut self, function_argument: ast::statement::FunctionArgument) -> io::Result<()> {
-		self.data_type(function_argument.data_type)?;
-		write!(self.writer, " {}", *function_argument.name)?;
+		self.type_checker.add_variable(Arc::new(Variable {
+			name: function_argument.name.clone(),
+			type_: function_argument.data_type.value.clone(),
+		}));
+		self.declaration(function_argument.data_type, &function_argument.name)
+	}
+
+	/// Emits the declaration of `name` with type `data_type`, or the type name for `sizeof` if `name` is empty.
+	///
+	/// Pointers and arrays are written around the name, e.g. `int name[2][3]` for `arr<arr<int>(3)>(2)` and
+	/// `int (*name)[3]` for `ptr arr<int>(3)`.
+	fn declaration(&mut self, data_type: PositionContainer<DataType>, name: &str) -> io::Result<()> {
+		let mut declarator = name.to_owned();
+		let mut data_type = data_type;
+		loop {
+			data_type = match data_type.value {
+				DataType::Pointer(pointee) => {
+					declarator = format!("*{}", declarator);
+					*pointee
+				},
+				DataType::Array { element, length } => {
+					// Array brackets bind stronger than the pointer star
+					if declarator.starts_with('*') {
+						declarator = format!("({})", declarator);
+					}
+					declarator = format!("{}[{}]", declarator, length);
+					*element
+				},
+				_ => break,
+			};
+		}
+		self.data_type(data_type)?;
+		if !declarator.is_empty() {
+			write!(self.writer, " {}", declarator)?;
+		}
 		Ok(())
 	}
 
@@ -202,13 +594,30 @@ impl Emitter {
 			DataType::Basic(basic_data_type) => self.basic_data_type(basic_data_type),
 			DataType::Struct(struct_name) => self.struct_name(struct_name),
 			DataType::Pointer(pointer) => self.pointer(*pointer),
+			// Arrays decay to pointers to their first element where they are not declared, see `Self::declaration`
+			DataType::Array { element, .. } => self.pointer(*element),
 		}
 	}
 
 	fn basic_data_type(&mut self, basic_data_type: ast::statement::BasicDataType) -> io::Result<()> {
 		match basic_data_type {
 			BasicDataType::Int => write!(self.writer, "int"),
+			BasicDataType::Int8 => write!(self.writer, "int8_t"),
+			BasicDataType::Int16 => write!(self.writer, "int16_t"),
+			BasicDataType::Int32 => write!(self.writer, "int32_t"),
+			BasicDataType::Int64 => write!(self.writer, "int64_t"),
+			BasicDataType::UInt8 => write!(self.writer, "uint8_t"),
+			BasicDataType::UInt16 => write!(self.writer, "uint16_t"),
+			BasicDataType::UInt32 => write!(self.writer, "uint32_t"),
+			BasicDataType::UInt64 => write!(self.writer, "uint64_t"),
 			BasicDataType::Float => write!(self.writer, "float"),
+			BasicDataType::Float32 => write!(self.writer, "float"),
+			BasicDataType::Float64 => write!(self.writer, "double"),
+			BasicDataType::Bool => write!(self.writer, "bool"),
+			BasicDataType::Char => write!(self.writer, "char"),
+			BasicDataType::Str => write!(self.writer, "ftl_str"),
+			// Only used as return type and behind pointers, i.e. as `void *`
+			BasicDataType::Nothing | BasicDataType::Any => write!(self.writer, "void"),
 		}
 	}
 
@@ -217,14 +626,45 @@ impl Emitter {
 	}
 
 	fn pointer(&mut self, pointer: PositionContainer<ast::statement::DataType>) -> io::Result<()> {
-		write!(self.writer, "*")?;
-		self.data_type(pointer)
+		self.data_type(pointer)?;
+		write!(self.writer, "*")
 	}
 
-	fn number(&mut self, number: ast::expression::Number) -> io::Result<()> {
+	/// Emits a number literal, which is negated if `is_negated`. Literals with suffix are cast to the exact type of the
+	/// suffix, like `((uint8_t)42u)`.
+	///
+	/// Negated literals are emitted as a whole like `((int8_t)-128)`, so that the minimum of a signed type keeps its
+	/// type instead of being negated after the cast.
+	fn number(&mut self, number: ast::expression::Number, is_negated: bool) -> io::Result<()> {
+		let sign = if is_negated { "-" } else { "" };
+		let (ast::expression::NumberKind::Int(_, suffix) | ast::expression::NumberKind::Float(_, suffix)) = *number;
+		if let Some(suffix) = suffix {
+			write!(self.writer, "((")?;
+			self.basic_data_type(BasicDataType::from(suffix))?;
+			write!(self.writer, ")")?;
+		}
 		match *number {
-			ast::expression::NumberKind::Int(int) => write!(self.writer, "{}", int)?,
-			ast::expression::NumberKind::Float(float) => write!(self.writer, "{}", float)?,
+			// The magnitude of the minimum of `int64` does not fit into any signed C type
+			ast::expression::NumberKind::Int(int, _) if is_negated && int > i64::MAX as u64 => {
+				write!(self.writer, "({}{} - 1)", sign, int - 1)?;
+			},
+			ast::expression::NumberKind::Int(int, suffix) => {
+				write!(self.writer, "{}{}", sign, int)?;
+				// Literals without suffix larger than `int64` can only be `uint64`
+				if suffix.is_some_and(|suffix| suffix.is_unsigned()) || int > i64::MAX as u64 {
+					write!(self.writer, "u")?;
+				}
+			},
+			// Debug formatting always includes a decimal point or exponent, so C does not treat the number as integer
+			ast::expression::NumberKind::Float(float, suffix) => {
+				write!(self.writer, "{}{:?}", sign, float)?;
+				if suffix == Some(NumberSuffix::F32) {
+					write!(self.writer, "f")?;
+				}
+			},
+		}
+		if suffix.is_some() {
+			write!(self.writer, ")")?;
 		}
 		Ok(())
 	}
@@ -234,3 +674,19 @@ impl Emitter {
 		Ok(())
 	}
 }
+
+/// Returns `text` as C compound literal of an `ftl_str`, like `(ftl_str){3, "FTL"}`.
+///
+/// Bytes other than printable ASCII are written as octal escape sequences, since hexadecimal escape sequences would
+/// also consume the following hex digits.
+fn c_string(text: &str) -> String {
+	let mut literal = format!("(ftl_str){{{}, \"", text.len());
+	for byte in text.bytes() {
+		match byte {
+			b'"' | b'\\' | b'?' => literal.extend(['\\', char::from(byte)]),
+			b' '..=b'~' => literal.push(char::from(byte)),
+			_ => literal += &format!("\\{:03o}", byte),
+		}
+	}
+	literal + "\"}"
+}
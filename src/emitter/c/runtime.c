@@ -0,0 +1,58 @@
+// Runtime of FTL programs, which is emitted at the start of each C file.
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Returns the array index `index`, which is checked against the array length if compiled with `-DFTL_DEBUG`.
+static inline long long ftl_index(long long index, long long length) {
+#ifdef FTL_DEBUG
+	if (index < 0 || index >= length) {
+		fprintf(stderr, "Index %lld out of bounds for array of length %lld\n", index, length);
+		abort();
+	}
+#endif
+	return index;
+}
+
+// A `str`, consisting of its length in bytes and its null-terminated UTF-8 bytes.
+//
+// Strings created at runtime, e.g. by concatenation, are never freed.
+typedef struct ftl_str {
+	int _len;
+	char *_cstr;
+} ftl_str;
+
+// Concatenates the strings `a` and `b`.
+static inline ftl_str ftl_str_concat(ftl_str a, ftl_str b) {
+	char *cstr = malloc(a._len + b._len + 1);
+	memcpy(cstr, a._cstr, a._len);
+	memcpy(cstr + a._len, b._cstr, b._len);
+	cstr[a._len + b._len] = '\0';
+	return (ftl_str){a._len + b._len, cstr};
+}
+
+// Compares the strings `a` and `b` byte by byte like `strcmp`.
+static inline int ftl_str_compare(ftl_str a, ftl_str b) {
+	int result = memcmp(a._cstr, b._cstr, a._len < b._len ? a._len : b._len);
+	if (result != 0) {
+		return result;
+	}
+	return (a._len > b._len) - (a._len < b._len);
+}
+
+// Creates a string like `printf`, used to interpolate values into strings.
+static inline ftl_str ftl_str_format(const char *format, ...) {
+	va_list args;
+	va_start(args, format);
+	int len = vsnprintf(NULL, 0, format, args);
+	va_end(args);
+	char *cstr = malloc(len + 1);
+	va_start(args, format);
+	vsnprintf(cstr, len + 1, format, args);
+	va_end(args);
+	return (ftl_str){len, cstr};
+}
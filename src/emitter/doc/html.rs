@@ -0,0 +1,94 @@
+//! HTML documentation emitter.
+
+use std::io;
+
+use super::{function_anchor, struct_anchor, Items, Signature, SignaturePart, TITLE};
+use crate::ast;
+
+/// Emits the API documentation as a static HTML page.
+pub struct Emitter {
+	writer: Box<dyn io::Write>,
+}
+
+impl crate::emitter::Emitter for Emitter {
+	fn codegen(
+		ast_nodes: impl Iterator<Item = ast::Commented<ast::Node>>,
+		writer: Box<dyn io::Write>,
+	) -> io::Result<()> {
+		let mut this = Self { writer };
+		let items = Items::new(ast_nodes);
+		writeln!(this.writer, "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">")?;
+		writeln!(this.writer, "<title>{}</title>\n</head>\n<body>\n<h1>{}</h1>", TITLE, TITLE)?;
+		if !items.structs.is_empty() {
+			writeln!(this.writer, "<h2>Structs</h2>")?;
+			for struct_ in &items.structs {
+				this.struct_(struct_, &items)?;
+			}
+		}
+		if !items.functions.is_empty() {
+			writeln!(this.writer, "<h2>Functions</h2>")?;
+			for (keyword, prototype) in &items.functions {
+				this.function(keyword, prototype, &items)?;
+			}
+		}
+		writeln!(this.writer, "</body>\n</html>")?;
+		Ok(())
+	}
+}
+
+impl Emitter {
+	fn struct_(&mut self, struct_: &ast::Struct, items: &Items) -> io::Result<()> {
+		writeln!(self.writer, "<h3 id=\"{}\">{}</h3>", struct_anchor(&struct_.name), escape(&struct_.name))?;
+		writeln!(self.writer, "<pre><code>struct {}</code></pre>", escape(&struct_.name))?;
+		self.doc(&struct_.doc)?;
+		if struct_.fields.is_empty() {
+			return Ok(());
+		}
+		writeln!(self.writer, "<table>\n<tr><th>Field</th><th>Type</th><th>Description</th></tr>")?;
+		for field in &struct_.fields {
+			let mut data_type = Signature::default();
+			items.data_type(&field.data_type, &mut data_type);
+			write!(self.writer, "<tr><td><code>{}</code></td><td><code>", escape(&field.name))?;
+			self.signature(&data_type)?;
+			write!(self.writer, "</code></td><td>")?;
+			self.doc(&field.doc)?;
+			writeln!(self.writer, "</td></tr>")?;
+		}
+		writeln!(self.writer, "</table>")?;
+		Ok(())
+	}
+
+	fn function(&mut self, keyword: &str, prototype: &ast::FunctionPrototype, items: &Items) -> io::Result<()> {
+		writeln!(self.writer, "<h3 id=\"{}\">{}</h3>", function_anchor(&prototype.name), escape(&prototype.name))?;
+		write!(self.writer, "<pre><code>")?;
+		self.signature(&items.signature(keyword, prototype))?;
+		writeln!(self.writer, "</code></pre>")?;
+		self.doc(&prototype.doc)
+	}
+
+	/// Emits the doc comment, with each block of lines separated by an empty line as paragraph.
+	fn doc(&mut self, doc: &Option<ast::Comment>) -> io::Result<()> {
+		for paragraph in doc.iter().flat_map(|doc| doc.split("\n\n")) {
+			writeln!(self.writer, "<p>{}</p>", escape(paragraph.trim()))?;
+		}
+		Ok(())
+	}
+
+	/// Emits the signature, with the struct names as links.
+	fn signature(&mut self, signature: &Signature) -> io::Result<()> {
+		for part in &signature.0 {
+			match part {
+				SignaturePart::Code(code) => write!(self.writer, "{}", escape(code))?,
+				SignaturePart::Struct(name) => {
+					write!(self.writer, "<a href=\"#{}\">{}</a>", struct_anchor(name), escape(name))?
+				},
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Escapes the characters with a special meaning in HTML.
+fn escape(text: &str) -> String {
+	text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
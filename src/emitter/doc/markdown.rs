@@ -0,0 +1,84 @@
+//! Markdown documentation emitter.
+
+use std::io;
+
+use super::{function_anchor, struct_anchor, Items, Signature, SignaturePart, TITLE};
+use crate::ast;
+
+/// Emits the API documentation as Markdown.
+pub struct Emitter {
+	writer: Box<dyn io::Write>,
+}
+
+impl crate::emitter::Emitter for Emitter {
+	fn codegen(
+		ast_nodes: impl Iterator<Item = ast::Commented<ast::Node>>,
+		writer: Box<dyn io::Write>,
+	) -> io::Result<()> {
+		let mut this = Self { writer };
+		let items = Items::new(ast_nodes);
+		writeln!(this.writer, "# {}", TITLE)?;
+		if !items.structs.is_empty() {
+			writeln!(this.writer, "\n## Structs")?;
+			for struct_ in &items.structs {
+				this.struct_(struct_, &items)?;
+			}
+		}
+		if !items.functions.is_empty() {
+			writeln!(this.writer, "\n## Functions")?;
+			for (keyword, prototype) in &items.functions {
+				this.function(keyword, prototype, &items)?;
+			}
+		}
+		Ok(())
+	}
+}
+
+impl Emitter {
+	fn struct_(&mut self, struct_: &ast::Struct, items: &Items) -> io::Result<()> {
+		writeln!(self.writer, "\n### <a id=\"{}\"></a>{}\n", struct_anchor(&struct_.name), *struct_.name)?;
+		writeln!(self.writer, "`struct {}`", *struct_.name)?;
+		self.doc(&struct_.doc)?;
+		if struct_.fields.is_empty() {
+			return Ok(());
+		}
+		writeln!(self.writer, "\n| Field | Type | Description |")?;
+		writeln!(self.writer, "| --- | --- | --- |")?;
+		for field in &struct_.fields {
+			let mut data_type = Signature::default();
+			items.data_type(&field.data_type, &mut data_type);
+			write!(self.writer, "| `{}` | ", *field.name)?;
+			self.signature(&data_type)?;
+			// Table cells can neither contain line breaks nor unescaped pipes
+			let description = field.doc.as_ref().map(|doc| doc.replace('|', "\\|").replace('\n', "<br>"));
+			writeln!(self.writer, " | {} |", description.unwrap_or_default())?;
+		}
+		Ok(())
+	}
+
+	fn function(&mut self, keyword: &str, prototype: &ast::FunctionPrototype, items: &Items) -> io::Result<()> {
+		writeln!(self.writer, "\n### <a id=\"{}\"></a>{}\n", function_anchor(&prototype.name), *prototype.name)?;
+		self.signature(&items.signature(keyword, prototype))?;
+		writeln!(self.writer)?;
+		self.doc(&prototype.doc)
+	}
+
+	/// Emits the doc comment as paragraph. Doc comments are written in Markdown, so they are emitted as they are.
+	fn doc(&mut self, doc: &Option<ast::Comment>) -> io::Result<()> {
+		if let Some(doc) = doc {
+			writeln!(self.writer, "\n{}", doc.value)?;
+		}
+		Ok(())
+	}
+
+	/// Emits the signature as inline code, with the struct names as links.
+	fn signature(&mut self, signature: &Signature) -> io::Result<()> {
+		for part in &signature.0 {
+			match part {
+				SignaturePart::Code(code) => write!(self.writer, "`{}`", code)?,
+				SignaturePart::Struct(name) => write!(self.writer, "[`{}`](#{})", name, struct_anchor(name))?,
+			}
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,104 @@
+//! Emitters generating API documentation from the `##` doc comments of functions, structs and fields.
+
+mod html;
+mod markdown;
+
+pub use html::Emitter as Html;
+pub use markdown::Emitter as Markdown;
+
+use crate::ast::{self, statement::DataType};
+
+/// Title of the generated documentation.
+const TITLE: &str = "API documentation";
+
+/// The documented items of a file.
+struct Items {
+	structs: Vec<ast::Struct>,
+	/// Function prototypes together with the keyword they are declared with, i.e. `def` or `extern`.
+	functions: Vec<(&'static str, ast::FunctionPrototype)>,
+}
+
+impl Items {
+	fn new(ast_nodes: impl Iterator<Item = ast::Commented<ast::Node>>) -> Self {
+		let mut items = Self { structs: Vec::new(), functions: Vec::new() };
+		for ast_node in ast_nodes {
+			match ast_node.node {
+				ast::Node::Function(function) => items.functions.push(("def", function.prototype)),
+				ast::Node::FunctionPrototype(prototype) => items.functions.push(("extern", prototype)),
+				ast::Node::Struct(struct_) => items.structs.push(struct_),
+			}
+		}
+		items
+	}
+
+	/// Returns the signature of a function, e.g. `def move(p: ptr Point, x: int): int`.
+	fn signature(&self, keyword: &str, prototype: &ast::FunctionPrototype) -> Signature {
+		let mut signature = Signature::default();
+		signature.code(&format!("{} {}(", keyword, *prototype.name));
+		for (i, arg) in prototype.args.iter().enumerate() {
+			if i != 0 {
+				signature.code(", ");
+			}
+			signature.code(&format!("{}: ", *arg.name));
+			self.data_type(&arg.data_type, &mut signature);
+		}
+		signature.code(")");
+		if let Some(return_type) = &prototype.return_type {
+			signature.code(": ");
+			self.data_type(return_type, &mut signature);
+		}
+		signature
+	}
+
+	/// Appends the `data_type` to the `signature`, with links to the structs defined in this file.
+	fn data_type(&self, data_type: &DataType, signature: &mut Signature) {
+		match data_type {
+			DataType::Basic(basic_data_type) => signature.code(&basic_data_type.to_string()),
+			DataType::Struct(name) if self.structs.iter().any(|struct_| *struct_.name == *name) => {
+				signature.0.push(SignaturePart::Struct(name.clone()))
+			},
+			DataType::Struct(name) => signature.code(name),
+			DataType::Pointer(pointer) => {
+				signature.code("ptr ");
+				self.data_type(pointer, signature);
+			},
+			DataType::Array { element, length } => {
+				signature.code("arr<");
+				self.data_type(element, signature);
+				signature.code(&format!(">({})", length));
+			},
+		}
+	}
+}
+
+/// Code like a function signature or a data type, in which structs defined in the same file get linked.
+#[derive(Debug, Default)]
+struct Signature(Vec<SignaturePart>);
+
+#[derive(Debug)]
+enum SignaturePart {
+	/// Code without links.
+	Code(String),
+	/// The name of a struct defined in the same file.
+	Struct(String),
+}
+
+impl Signature {
+	/// Appends `code`, merging it with the preceding code.
+	fn code(&mut self, code: &str) {
+		match self.0.last_mut() {
+			Some(SignaturePart::Code(last)) => last.push_str(code),
+			_ => self.0.push(SignaturePart::Code(code.to_owned())),
+		}
+	}
+}
+
+/// Returns the id of the anchor of a struct, which is the target of links to it.
+fn struct_anchor(name: &str) -> String {
+	format!("struct.{}", name)
+}
+
+/// Returns the id of the anchor of a function.
+fn function_anchor(name: &str) -> String {
+	format!("fn.{}", name)
+}
@@ -5,8 +5,9 @@ use std::io;
 use crate::{
 	ast::{
 		self,
-		expression::BinaryOperator,
-		statement::{BasicDataType, DataType},
+		expression::{BinaryOperator, StringPart, UnaryOperator},
+		for_loop::Iterable,
+		statement::DataType,
 		Expression,
 	},
 	source::PositionContainer,
@@ -20,7 +21,10 @@ pub struct Emitter {
 }
 
 impl super::Emitter for Emitter {
-	fn codegen(ast_nodes: impl Iterator<Item = ast::Node>, writer: Box<dyn io::Write>) -> io::Result<()> {
+	fn codegen(
+		ast_nodes: impl Iterator<Item = ast::Commented<ast::Node>>,
+		writer: Box<dyn io::Write>,
+	) -> io::Result<()> {
 		let mut this = Self { writer };
 		for ast_node in ast_nodes {
 			this.ast_node(ast_node)?;
@@ -31,84 +35,334 @@ impl super::Emitter for Emitter {
 
 /// Each of the functions in this impl block is responsible for emitting the corresponding AST node.
 impl Emitter {
-	fn ast_node(&mut self, node: ast::Node) -> io::Result<()> {
-		match node {
-			ast::Node::Function(function) => self.function(function),
-			ast::Node::Struct(struct_) => self.struct_(struct_),
-			_ => todo!(),
+	fn ast_node(&mut self, node: ast::Commented<ast::Node>) -> io::Result<()> {
+		self.leading_comments(&node.comments.leading)?;
+		let doc = match &node.node {
+			ast::Node::Function(function) => &function.prototype.doc,
+			ast::Node::FunctionPrototype(prototype) => &prototype.doc,
+			ast::Node::Struct(struct_) => &struct_.doc,
+		};
+		self.doc_comment(doc)?;
+		match node.node {
+			ast::Node::Function(function) => self.function(function)?,
+			ast::Node::Struct(struct_) => self.struct_(struct_)?,
+			ast::Node::FunctionPrototype(prototype) => {
+				write!(self.writer, "extern ")?;
+				self.function_prototype(prototype)?
+			},
+		}
+		self.line_end(&node.comments.trailing)?;
+		self.leading_comments(&node.comments.following)
+	}
+
+	/// Emits each comment on its own line.
+	fn leading_comments(&mut self, comments: &[ast::Comment]) -> io::Result<()> {
+		for comment in comments {
+			self.comment(comment)?;
+			writeln!(self.writer)?;
+		}
+		Ok(())
+	}
+
+	/// Emits a line comment or block comment, without a newline at the end.
+	fn comment(&mut self, comment: &ast::Comment) -> io::Result<()> {
+		// The text of a block comment contains its delimiters
+		if comment.starts_with("#[") && comment.ends_with("]#") {
+			write!(self.writer, "{}", comment.value)
+		} else {
+			write!(self.writer, "# {}", comment.value)
+		}
+	}
+
+	/// Emits each line of the doc comment prefixed with `##`.
+	fn doc_comment(&mut self, doc: &Option<ast::Comment>) -> io::Result<()> {
+		for line in doc.iter().flat_map(|doc| doc.value.lines()) {
+			if line.is_empty() {
+				writeln!(self.writer, "##")?;
+			} else {
+				writeln!(self.writer, "## {}", line)?;
+			}
 		}
+		Ok(())
+	}
+
+	/// Ends the line, after the first of the `trailing` comments if there are any.
+	fn line_end(&mut self, trailing: &[ast::Comment]) -> io::Result<()> {
+		match trailing.split_first() {
+			Some((first, rest)) => {
+				write!(self.writer, " ")?;
+				self.comment(first)?;
+				writeln!(self.writer)?;
+				self.leading_comments(rest)
+			},
+			None => writeln!(self.writer),
+		}
+	}
+
+	/// Emits comma separated elements, e.g. function arguments, together with their comments.
+	///
+	/// `comments` are the comments inside an empty list.
+	fn list<T>(
+		&mut self,
+		elements: Vec<ast::Commented<T>>,
+		comments: &[ast::Comment],
+		mut element: impl FnMut(&mut Self, T) -> io::Result<()>,
+	) -> io::Result<()> {
+		// A comment inside the parentheses has to end with a newline, otherwise it would swallow the rest
+		self.leading_comments(comments)?;
+		let len = elements.len();
+		let mut at_line_start = false;
+		for (i, commented) in elements.into_iter().enumerate() {
+			if !commented.comments.leading.is_empty() {
+				// Leading comments need to start on a new line, so that they are not taken as trailing comments
+				if !at_line_start {
+					writeln!(self.writer)?;
+				}
+				self.leading_comments(&commented.comments.leading)?;
+			}
+			element(self, commented.node)?;
+			let is_last = i + 1 == len;
+			if !is_last {
+				write!(self.writer, ",")?;
+			}
+			// A comment inside the parentheses has to end with a newline, otherwise it would swallow the rest
+			for comment in &commented.comments.trailing {
+				write!(self.writer, " ")?;
+				self.comment(comment)?;
+				writeln!(self.writer)?;
+			}
+			at_line_start = !commented.comments.trailing.is_empty();
+			if !is_last && !at_line_start {
+				write!(self.writer, " ")?;
+			}
+		}
+		Ok(())
 	}
 
 	fn function(&mut self, function: ast::FunctionDefinition) -> io::Result<()> {
 		// Function header
-		write!(self.writer, "function {}(", *function.prototype.name)?;
-		for arg in function.prototype.args {
-			self.function_argument(arg)?;
-			write!(self.writer, ", ")?; // TODO: Remove trailing comma
-		}
-		writeln!(self.writer, ") {{")?;
+		write!(self.writer, "def ")?;
+		self.function_prototype(function.prototype)?;
+		writeln!(self.writer, " {{")?;
 
 		// Function body
-		for instruction in function.body {
-			self.instruction(instruction)?;
-		}
+		self.block(function.body)?;
 		writeln!(self.writer)?;
-		writeln!(self.writer, "}}")?;
+		write!(self.writer, "}}")?;
+		Ok(())
+	}
+
+	fn function_prototype(&mut self, prototype: ast::FunctionPrototype) -> io::Result<()> {
+		write!(self.writer, "{}(", *prototype.name)?;
+		self.list(prototype.args, &prototype.comments, Self::function_argument)?;
+		write!(self.writer, ")")?;
+		if let Some(return_type) = prototype.return_type {
+			write!(self.writer, ": ")?;
+			self.data_type(return_type)?;
+		}
 		Ok(())
 	}
 
 	fn struct_(&mut self, struct_: ast::Struct) -> io::Result<()> {
 		writeln!(self.writer, "struct {} {{", *struct_.name)?;
 		for field in struct_.fields {
+			self.leading_comments(&field.comments.leading)?;
+			self.doc_comment(&field.doc)?;
 			write!(self.writer, "{}: ", *field.name)?;
-			self.data_type(field.data_type)?;
-			writeln!(self.writer, ", ")?; // TODO: Remove trailing comma
+			self.data_type(field.node.data_type)?;
+			self.line_end(&field.comments.trailing)?;
+			self.leading_comments(&field.comments.following)?;
 		}
-		writeln!(self.writer, "}}")?;
+		write!(self.writer, "}}")?;
 		Ok(())
 	}
 
-	fn instruction(&mut self, instruction: ast::Instruction) -> io::Result<()> {
-		match instruction {
-			ast::Instruction::Expression(expression) => self.expression(expression),
-			ast::Instruction::Statement(statement) => self.statement(statement),
-			ast::Instruction::IfElse(if_else) => self.if_else(*if_else),
-			ast::Instruction::WhileLoop(while_loop) => self.while_loop(*while_loop),
+	/// Emits the instructions of a block, followed by the comments at the end of the block.
+	fn block(&mut self, block: ast::Block) -> io::Result<()> {
+		for instruction in block.instructions {
+			self.instruction(instruction)?;
+		}
+		self.leading_comments(&block.comments)
+	}
+
+	fn instruction(&mut self, instruction: ast::Commented<ast::Instruction>) -> io::Result<()> {
+		self.leading_comments(&instruction.comments.leading)?;
+		match instruction.node {
+			ast::Instruction::Expression(expression) => self.expression(expression)?,
+			ast::Instruction::Statement(statement) => self.statement(statement)?,
+			ast::Instruction::IfElse(if_else) => self.if_else(*if_else)?,
+			ast::Instruction::WhileLoop(while_loop) => self.while_loop(*while_loop)?,
+			ast::Instruction::ForLoop(for_loop) => self.for_loop(*for_loop)?,
 		}
+		self.line_end(&instruction.comments.trailing)
 	}
 
 	fn expression(&mut self, expression: ast::Expression) -> io::Result<()> {
 		match expression {
 			Expression::BinaryExpression(binary_expression) => self.binary_expression(binary_expression),
+			Expression::Unary(unary_expression) => self.unary_expression(unary_expression),
 			Expression::FunctionCall(function_call) => self.function_call(function_call),
+			Expression::FieldAccess(field_access) => self.field_access(field_access),
+			Expression::StructLiteral(struct_literal) => self.struct_literal(struct_literal),
+			Expression::Index(index) => self.index(index),
+			Expression::Alloc(alloc) => self.alloc(alloc),
+			Expression::String(string_literal) => self.string_literal(string_literal),
 			Expression::Number(number) => self.number(number),
 			Expression::Variable(variable) => self.variable(variable),
+			Expression::Nil(_) => write!(self.writer, "nil"),
+		}
+	}
+
+	/// Emits a field access, whose base is enclosed in parentheses if it contains an operator, since field accesses
+	/// bind stronger than any operator.
+	fn field_access(&mut self, field_access: ast::expression::FieldAccess) -> io::Result<()> {
+		let needs_parentheses = matches!(*field_access.base, Expression::BinaryExpression(_) | Expression::Unary(_));
+		if needs_parentheses {
+			write!(self.writer, "(")?;
 		}
+		self.expression(*field_access.base)?;
+		if needs_parentheses {
+			write!(self.writer, ")")?;
+		}
+		write!(self.writer, ".{}", *field_access.field)
+	}
+
+	/// Emits an index like `a@i`. Like for field accesses, a base containing an operator is enclosed in parentheses.
+	/// The index is only written without parentheses if it is a single operand, since e.g. `a@i.b` means `(a@i).b`.
+	fn index(&mut self, index: ast::expression::Index) -> io::Result<()> {
+		let base_needs_parentheses = matches!(*index.base, Expression::BinaryExpression(_) | Expression::Unary(_));
+		if base_needs_parentheses {
+			write!(self.writer, "(")?;
+		}
+		self.expression(*index.base)?;
+		if base_needs_parentheses {
+			write!(self.writer, ")")?;
+		}
+		write!(self.writer, "@")?;
+		let index_needs_parentheses = !matches!(
+			*index.index,
+			Expression::Variable(_)
+				| Expression::Number(_)
+				| Expression::FunctionCall(_)
+				| Expression::StructLiteral(_)
+		);
+		if index_needs_parentheses {
+			write!(self.writer, "(")?;
+		}
+		self.expression(*index.index)?;
+		if index_needs_parentheses {
+			write!(self.writer, ")")?;
+		}
+		Ok(())
+	}
+
+	/// Emits a string literal, in which quotes, backslashes, control characters and braces are escaped again.
+	fn string_literal(&mut self, string_literal: ast::expression::StringLiteral) -> io::Result<()> {
+		write!(self.writer, "\"")?;
+		for part in string_literal.parts {
+			let text = match part {
+				StringPart::Text(text) => text,
+				StringPart::Interpolation(value) => {
+					write!(self.writer, "{{")?;
+					self.expression(value)?;
+					write!(self.writer, "}}")?;
+					continue;
+				},
+			};
+			for char_ in text.chars() {
+				match char_ {
+					'"' => write!(self.writer, "\\\"")?,
+					'\\' => write!(self.writer, "\\\\")?,
+					'\n' => write!(self.writer, "\\n")?,
+					'\r' => write!(self.writer, "\\r")?,
+					'\t' => write!(self.writer, "\\t")?,
+					'\0' => write!(self.writer, "\\0")?,
+					'{' => write!(self.writer, "{{{{")?,
+					'}' => write!(self.writer, "}}}}")?,
+					char_ if char_.is_control() => write!(self.writer, "\\u{{{:x}}}", u32::from(char_))?,
+					char_ => write!(self.writer, "{}", char_)?,
+				}
+			}
+		}
+		write!(self.writer, "\"")
+	}
+
+	fn alloc(&mut self, alloc: ast::expression::Alloc) -> io::Result<()> {
+		write!(self.writer, "alloc ")?;
+		self.data_type(alloc.data_type)
+	}
+
+	fn struct_literal(&mut self, struct_literal: ast::expression::StructLiteral) -> io::Result<()> {
+		write!(self.writer, "new {}(", *struct_literal.name)?;
+		self.list(struct_literal.fields, &struct_literal.comments, |this, initializer| {
+			write!(this.writer, "{}=", *initializer.name)?;
+			this.expression(initializer.value)
+		})?;
+		write!(self.writer, ")")
 	}
 
 	fn binary_expression(&mut self, binary_expression: ast::expression::BinaryExpression) -> io::Result<()> {
-		self.expression(*binary_expression.lhs)?;
-		let operator = match *binary_expression.operator {
-			ast::expression::BinaryOperator::Add => "+",
-			ast::expression::BinaryOperator::Subtract => "-",
-			ast::expression::BinaryOperator::Multiply => "*",
-			ast::expression::BinaryOperator::Divide => "/",
-			BinaryOperator::Less => "<",
-			BinaryOperator::Greater => ">",
-			BinaryOperator::Equal => "==",
-			BinaryOperator::NotEqual => "=/=",
+		let operator = binary_expression.operator.value;
+		self.operand(*binary_expression.lhs, Some(&operator), false)?;
+		write!(self.writer, " {}", operator)?;
+		self.operator_end(&binary_expression.comments, " ")?;
+		self.operand(*binary_expression.rhs, Some(&operator), true)?;
+		Ok(())
+	}
+
+	fn unary_expression(&mut self, unary_expression: ast::expression::UnaryExpression) -> io::Result<()> {
+		write!(self.writer, "{}", *unary_expression.operator)?;
+		let separator = match *unary_expression.operator {
+			UnaryOperator::Negate => "",
+			// Keyword operators need to be separated from their operand
+			UnaryOperator::Not | UnaryOperator::Complement | UnaryOperator::Reference | UnaryOperator::Dereference => {
+				" "
+			},
+		};
+		self.operator_end(&unary_expression.comments, separator)?;
+		self.operand(*unary_expression.operand, None, false)
+	}
+
+	/// Separates an operator from its operand by `separator`, or by the `comments` between them, which end the line.
+	fn operator_end(&mut self, comments: &[ast::Comment], separator: &str) -> io::Result<()> {
+		match comments.split_first() {
+			Some((first, rest)) => {
+				write!(self.writer, " ")?;
+				self.comment(first)?;
+				writeln!(self.writer)?;
+				self.leading_comments(rest)
+			},
+			None => write!(self.writer, "{}", separator),
+		}
+	}
+
+	/// Emits the operand of an operator, enclosed in parentheses if it would bind differently without them.
+	///
+	/// `operator` is the binary operator the operand belongs to, or `None` for the operand of a unary operator.
+	fn operand(&mut self, operand: ast::Expression, operator: Option<&BinaryOperator>, is_rhs: bool) -> io::Result<()> {
+		let needs_parentheses = match (&operand, operator) {
+			// Operators of the same precedence group from the left, so they need parentheses on the right
+			(Expression::BinaryExpression(inner), Some(operator)) => {
+				inner.operator.precedence() < operator.precedence()
+					|| (inner.operator.precedence() == operator.precedence() && is_rhs)
+			},
+			(Expression::BinaryExpression(_), None) => true,
+			_ => false,
 		};
-		write!(self.writer, " {} ", operator)?;
-		self.expression(*binary_expression.rhs)?;
+		if needs_parentheses {
+			write!(self.writer, "(")?;
+		}
+		self.expression(operand)?;
+		if needs_parentheses {
+			write!(self.writer, ")")?;
+		}
 		Ok(())
 	}
 
 	fn function_call(&mut self, function_call: ast::expression::FunctionCall) -> io::Result<()> {
 		write!(self.writer, "{}(", *function_call.name)?;
-		for param in function_call.params {
-			self.expression(param)?;
-		}
-		writeln!(self.writer, ")")?;
+		self.list(function_call.params, &function_call.comments, Self::expression)?;
+		write!(self.writer, ")")?;
 		Ok(())
 	}
 
@@ -118,62 +372,117 @@ impl Emitter {
 				self.variable_declaration(variable_declaration)
 			},
 			ast::statement::Statement::VariableAssignment(assignment) => self.assignment(assignment),
-			ast::Statement::Return(expression) => self.return_(expression),
+			ast::Statement::Return(return_) => self.return_(return_),
+			ast::Statement::Delete(pointer) => self.delete(pointer),
+			ast::Statement::Break(jump) => self.jump("break", jump),
+			ast::Statement::Continue(jump) => self.jump("continue", jump),
 		}
 	}
 
 	fn variable_declaration(&mut self, variable_declaration: ast::statement::VariableDeclaration) -> io::Result<()> {
-		write!(self.writer, "var {} = ", *variable_declaration.name)?;
-		self.expression(variable_declaration.value)?;
-		writeln!(self.writer)?;
+		write!(self.writer, "var {}: ", *variable_declaration.name)?;
+		self.data_type(variable_declaration.data_type)?;
+		if let Some(value) = variable_declaration.value {
+			write!(self.writer, " = ")?;
+			self.expression(value)?;
+		}
 		Ok(())
 	}
 
 	fn assignment(&mut self, assignment: ast::statement::VariableAssignment) -> io::Result<()> {
-		write!(self.writer, "{} = ", *assignment.name)?;
+		self.expression(assignment.target)?;
+		write!(self.writer, " = ")?;
 		self.expression(assignment.value)?;
-		writeln!(self.writer)?;
 		Ok(())
 	}
 
-	fn return_(&mut self, expression: ast::Expression) -> io::Result<()> {
-		write!(self.writer, "return ")?;
-		self.expression(expression)?;
-		writeln!(self.writer)?;
+	fn return_(&mut self, return_: ast::statement::Return) -> io::Result<()> {
+		write!(self.writer, "return")?;
+		if let Some(value) = return_.value {
+			write!(self.writer, " ")?;
+			self.expression(value)?;
+		}
+		Ok(())
+	}
+
+	fn delete(&mut self, pointer: ast::Expression) -> io::Result<()> {
+		write!(self.writer, "del ")?;
+		self.expression(pointer)
+	}
+
+	fn jump(&mut self, keyword: &str, jump: ast::statement::Jump) -> io::Result<()> {
+		write!(self.writer, "{}", keyword)?;
+		if let Some(label) = jump.label {
+			write!(self.writer, " {}", *label)?;
+		}
 		Ok(())
 	}
 
+	/// Emits the label of a loop like `outer: `, if there is one.
+	fn loop_label(&mut self, label: Option<PositionContainer<String>>) -> io::Result<()> {
+		match label {
+			Some(label) => write!(self.writer, "{}: ", *label),
+			None => Ok(()),
+		}
+	}
+
 	fn if_else(&mut self, if_else: ast::IfElse) -> io::Result<()> {
 		// if block, always present
 		write!(self.writer, "if (")?;
 		self.expression(if_else.condition)?;
 		writeln!(self.writer, ") {{")?;
-		for instruction in if_else.if_true {
-			self.instruction(instruction)?;
+		self.block(if_else.if_true)?;
+		// `else` must be on the same line as the closing `}`, since a newline ends the if
+		for else_if in if_else.else_ifs {
+			write!(self.writer, "}} else if (")?;
+			self.expression(else_if.condition)?;
+			writeln!(self.writer, ") {{")?;
+			self.block(else_if.body)?;
 		}
-		writeln!(self.writer, "}}")?;
-
 		// else block, optional
-		if if_else.if_false.is_empty() {
+		if if_else.if_false.instructions.is_empty() && if_else.if_false.comments.is_empty() {
+			write!(self.writer, "}}")?;
 			return Ok(());
 		}
-		writeln!(self.writer, "else {{")?;
-		for instruction in if_else.if_false {
-			self.instruction(instruction)?;
-		}
-		writeln!(self.writer, "}}")?;
+		writeln!(self.writer, "}} else {{")?;
+		self.block(if_else.if_false)?;
+		write!(self.writer, "}}")?;
 
 		Ok(())
 	}
 
 	fn while_loop(&mut self, while_loop: ast::WhileLoop) -> io::Result<()> {
+		self.loop_label(while_loop.label)?;
 		write!(self.writer, "while (")?;
 		self.expression(while_loop.condition)?;
 		writeln!(self.writer, ") {{")?;
-		for instruction in while_loop.body {
-			self.instruction(instruction)?;
+		self.block(while_loop.body)?;
+		write!(self.writer, "}}")?;
+		Ok(())
+	}
+
+	fn for_loop(&mut self, for_loop: ast::ForLoop) -> io::Result<()> {
+		self.loop_label(for_loop.label)?;
+		write!(self.writer, "for {} ", *for_loop.variable)?;
+		match for_loop.iterable {
+			Iterable::Range(range) => {
+				write!(self.writer, "in ")?;
+				self.expression(range.start)?;
+				write!(self.writer, "{}", if range.inclusive { "..=" } else { ".." })?;
+				self.expression(range.end)?;
+				if let Some(step) = range.step {
+					write!(self.writer, " step ")?;
+					self.expression(step)?;
+				}
+			},
+			Iterable::Array(array) => {
+				write!(self.writer, "of ")?;
+				self.expression(array)?;
+			},
 		}
-		writeln!(self.writer, "}}")?;
+		writeln!(self.writer, " {{")?;
+		self.block(for_loop.body)?;
+		write!(self.writer, "}}")?;
 		Ok(())
 	}
 
@@ -188,14 +497,12 @@ impl Emitter {
 			DataType::Basic(basic_data_type) => self.basic_data_type(basic_data_type),
 			DataType::Struct(struct_name) => self.struct_name(struct_name),
 			DataType::Pointer(pointer) => self.pointer(*pointer),
+			DataType::Array { element, length } => self.array(*element, length),
 		}
 	}
 
 	fn basic_data_type(&mut self, basic_data_type: ast::statement::BasicDataType) -> io::Result<()> {
-		match basic_data_type {
-			BasicDataType::Int => write!(self.writer, "int"),
-			BasicDataType::Float => write!(self.writer, "float"),
-		}
+		write!(self.writer, "{}", basic_data_type)
 	}
 
 	fn struct_name(&mut self, struct_name: String) -> io::Result<()> {
@@ -203,14 +510,29 @@ impl Emitter {
 	}
 
 	fn pointer(&mut self, pointer: PositionContainer<ast::statement::DataType>) -> io::Result<()> {
-		write!(self.writer, "ptr")?;
+		write!(self.writer, "ptr ")?;
 		self.data_type(pointer)
 	}
 
+	fn array(&mut self, element: PositionContainer<ast::statement::DataType>, length: u64) -> io::Result<()> {
+		write!(self.writer, "arr<")?;
+		self.data_type(element)?;
+		write!(self.writer, ">({})", length)
+	}
+
 	fn number(&mut self, number: ast::expression::Number) -> io::Result<()> {
-		match *number {
-			ast::expression::NumberKind::Int(int) => write!(self.writer, "{}", int)?,
-			ast::expression::NumberKind::Float(float) => write!(self.writer, "{}", float)?,
+		let suffix = match *number {
+			ast::expression::NumberKind::Int(int, suffix) => {
+				write!(self.writer, "{}", int)?;
+				suffix
+			},
+			ast::expression::NumberKind::Float(float, suffix) => {
+				write!(self.writer, "{:?}", float)?;
+				suffix
+			},
+		};
+		if let Some(suffix) = suffix {
+			write!(self.writer, "{}", suffix)?;
 		}
 		Ok(())
 	}
@@ -1,16 +1,20 @@
 //! Generating a specific target code from AST nodes.
 
 mod c;
+mod doc;
 mod ftl;
+#[cfg(test)]
+mod test;
 
 pub use c::Emitter as C;
+pub use doc::{Html, Markdown};
 pub use ftl::Emitter as Ftl;
 
 /// Generates (target) code from AST nodes.
 pub trait Emitter {
 	/// Generate code from the AST nodes and write it to the `writer`.
 	fn codegen(
-		ast_nodes: impl Iterator<Item = crate::ast::Node>,
+		ast_nodes: impl Iterator<Item = crate::ast::Commented<crate::ast::Node>>,
 		writer: Box<dyn std::io::Write>,
 	) -> std::io::Result<()>;
 }
@@ -0,0 +1,474 @@
+use std::{cell::RefCell, io, rc::Rc, sync::Arc};
+
+use crate::{
+	emitter::{c::RUNTIME, Emitter},
+	lexer::Lexer,
+	parser::Parser,
+	semantic_analyzer::{SymbolTable, TypeChecker},
+	source::Source,
+};
+
+/// Tests that the loop variable over an array of arrays points into the outer array, since C cannot copy arrays.
+#[test]
+fn test_c_array_of_arrays_loop() {
+	assert_eq!(
+		c("def main() {
+			var matrix: arr<arr<int>(2)>(3)
+			var sum: int = 0
+			for row of matrix {
+				for x of row {
+					sum = sum + x
+				}
+			}
+		}"),
+		"void main() {
+int matrix[3][2] = {0};
+int sum = 0;
+for (long long ftl_index_row = 0; ftl_index_row < 3; ftl_index_row++) {
+int *row = (matrix)[ftl_index_row];
+for (long long ftl_index_x = 0; ftl_index_x < 2; ftl_index_x++) {
+int x = (row)[ftl_index_x];
+sum = (sum + x);
+}
+}
+
+}
+"
+	);
+}
+
+/// Tests that array fields left out of a struct literal are zero-initialized by C.
+#[test]
+fn test_c_struct_literal_without_array_fields() {
+	assert_eq!(
+		c("struct Bag {
+			items: arr<int>(3)
+			count: int
+		}
+		struct Empty {
+			items: arr<int>(2)
+		}
+		def main() {
+			var bag: Bag = new Bag(count = 1)
+			var empty: Empty = new Empty()
+		}"),
+		"typedef struct Bag Bag;
+struct Bag {
+int items[3];int count;};
+typedef struct Empty Empty;
+struct Empty {
+int items[2];};
+void main() {
+Bag bag = (Bag){.count = 1};
+Empty empty = (Empty){0};
+
+}
+"
+	);
+}
+
+/// Tests that number literals keep their value and type in C, including the minimums of signed types.
+#[test]
+fn test_c_number_literals() {
+	assert_eq!(
+		c("def main() {
+			var a: int8 = -128i8
+			var b: int64 = -9223372036854775808
+			var c: uint64 = 18446744073709551615
+			var d: float32 = 1.5f32
+		}"),
+		"void main() {
+int8_t a = (((int8_t)-128));
+int64_t b = ((-9223372036854775807 - 1));
+uint64_t c = 18446744073709551615u;
+float d = ((float)1.5f);
+
+}
+"
+	);
+}
+
+/// Tests that `else if` chains stay flat and that labeled jumps out of for loops become `goto`s.
+#[test]
+fn test_c_control_flow() {
+	assert_eq!(
+		c("def main(n: int): int {
+			var sum: int = 0
+			if n < 0 {
+				sum = 1
+			} else if n == 0 {
+				sum = 2
+			} else {
+				sum = 3
+			}
+			outer: for i in 0..n {
+				for j in 10..=0 step -2 {
+					if j == i {
+						continue outer
+					}
+					if j > 8 {
+						break
+					}
+				}
+				while sum > 100 {
+					break outer
+				}
+			}
+			return sum
+		}"),
+		"int main(int n) {
+int sum = 0;
+if ((n < 0)) {
+sum = 1;
+}
+else if ((n == 0)) {
+sum = 2;
+}
+else {
+sum = 3;
+}
+for (int i = 0, ftl_end_i = n; i < ftl_end_i; i++) {
+for (int j = 10, ftl_end_j = 0, ftl_step_j = (-2); ftl_step_j > 0 ? j <= ftl_end_j : j >= ftl_end_j; j += ftl_step_j) {
+if ((j == i)) {
+goto ftl_continue_outer_1;
+}
+if ((j > 8)) {
+break;
+}
+}
+while ((sum > 100)) {
+goto ftl_break_outer_1;
+}
+ftl_continue_outer_1: ;
+}
+ftl_break_outer_1: ;
+return sum;
+
+}
+"
+	);
+}
+
+/// Tests that struct literals become compound literals, that fields are accessed with `->` through pointers and that
+/// the pointer operations map to their C counterparts.
+#[test]
+fn test_c_structs_and_pointers() {
+	assert_eq!(
+		c("struct Node {
+			value: int
+			next: ptr Node
+		}
+		def main() {
+			var head: Node = new Node(value = 1, next = nil)
+			var p: ptr Node = alloc Node
+			p.next = ref head
+			deref p = head
+			p.value = head.value + p.next.value
+			del p
+		}"),
+		"typedef struct Node Node;
+struct Node {
+int value;Node *next;};
+void main() {
+Node head = (Node){.value = 1, .next = NULL};
+Node *p = calloc(1, sizeof(Node));
+p->next = (&head);
+(*p) = head;
+p->value = (head.value + p->next->value);
+free(p);
+
+}
+"
+	);
+}
+
+/// Tests that names starting with `_` do not collide with the helper variables of loops, which start with `ftl_`.
+#[test]
+fn test_c_loop_helper_names() {
+	assert_eq!(
+		c("def main(n: int) {
+			for i in 0..n step 2 {
+				var _end_i: int = i
+			}
+		}"),
+		"void main(int n) {
+for (int i = 0, ftl_end_i = n, ftl_step_i = 2; ftl_step_i > 0 ? i < ftl_end_i : i > ftl_end_i; i += ftl_step_i) {
+int _end_i = i;
+}
+
+}
+"
+	);
+}
+
+/// Tests that string literals, interpolation and concatenation use the string runtime.
+#[test]
+fn test_c_strings() {
+	assert_eq!(
+		c(r#"def main() {
+			var name: str = "FTL"
+			var greeting: str = "Hello {name}" + "!"
+		}"#),
+		r#"void main() {
+ftl_str name = (ftl_str){3, "FTL"};
+ftl_str greeting = ftl_str_concat(ftl_str_concat((ftl_str){6, "Hello "}, name), (ftl_str){1, "!"});
+
+}
+"#
+	);
+}
+
+/// Tests that formatting the formatted code does not change it.
+#[test]
+fn test_ftl_round_trip() {
+	let formatted = r#"struct Node {
+value: int
+next: ptr Node
+}
+def main(n: int): int {
+var sum: int = 0
+if (n < 0) {
+sum = 1
+} else if (n == 0) {
+sum = 2
+} else {
+sum = 3
+}
+outer: for i in 0..n {
+for j in 10..=0 step -2 {
+if (j == i) {
+continue outer
+}
+if (j > 8) {
+break
+}
+}
+while (sum > 100) {
+break outer
+}
+}
+var head: Node = new Node(value=1, next=nil)
+var p: ptr Node = alloc Node
+p.next = ref head
+deref p = head
+p.value = head.value + p.next.value
+del p
+var greeting: str = "Hello {sum}" + "!"
+return sum
+
+}
+"#;
+	assert_eq!(emit::<crate::emitter::Ftl>(formatted), formatted);
+}
+
+/// Tests that doc comments are kept and that comments after the last field or node stay on their own lines, even if
+/// they are stray doc comments.
+#[test]
+fn test_ftl_doc_comments() {
+	assert_eq!(
+		emit::<crate::emitter::Ftl>(DOCUMENTED),
+		"## A point in the plane.
+struct Point {
+## Horizontal position.
+x: int
+y: int # no doc
+# after the last field
+}
+## Moves `p` by `dx`.
+##
+## Returns the new x.
+def move(p: ptr Point, dx: int): int {
+p.x = p.x + dx
+return p.x
+
+}
+extern puts(s: ptr any): int32
+# stray doc
+"
+	);
+}
+
+/// Tests the Markdown documentation, including links to structs in signatures.
+#[test]
+fn test_markdown() {
+	assert_eq!(
+		emit::<crate::emitter::Markdown>(DOCUMENTED),
+		"# API documentation
+
+## Structs
+
+### <a id=\"struct.Point\"></a>Point
+
+`struct Point`
+
+A point in the plane.
+
+| Field | Type | Description |
+| --- | --- | --- |
+| `x` | `int` | Horizontal position. |
+| `y` | `int` |  |
+
+## Functions
+
+### <a id=\"fn.move\"></a>move
+
+`def move(p: ptr `[`Point`](#struct.Point)`, dx: int): int`
+
+Moves `p` by `dx`.
+
+Returns the new x.
+
+### <a id=\"fn.puts\"></a>puts
+
+`extern puts(s: ptr any): int32`
+"
+	);
+}
+
+/// Tests the HTML documentation, including links to structs in signatures.
+#[test]
+fn test_html() {
+	assert_eq!(
+		emit::<crate::emitter::Html>(DOCUMENTED),
+		r##"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>API documentation</title>
+</head>
+<body>
+<h1>API documentation</h1>
+<h2>Structs</h2>
+<h3 id="struct.Point">Point</h3>
+<pre><code>struct Point</code></pre>
+<p>A point in the plane.</p>
+<table>
+<tr><th>Field</th><th>Type</th><th>Description</th></tr>
+<tr><td><code>x</code></td><td><code>int</code></td><td><p>Horizontal position.</p>
+</td></tr>
+<tr><td><code>y</code></td><td><code>int</code></td><td></td></tr>
+</table>
+<h2>Functions</h2>
+<h3 id="fn.move">move</h3>
+<pre><code>def move(p: ptr <a href="#struct.Point">Point</a>, dx: int): int</code></pre>
+<p>Moves `p` by `dx`.</p>
+<p>Returns the new x.</p>
+<h3 id="fn.puts">puts</h3>
+<pre><code>extern puts(s: ptr any): int32</code></pre>
+</body>
+</html>
+"##
+	);
+}
+
+/// Source code with doc comments on structs, fields and functions, ending with a stray doc comment.
+const DOCUMENTED: &str = "## A point in the plane.
+struct Point {
+	## Horizontal position.
+	x: int
+	y: int # no doc
+	# after the last field
+}
+
+## Moves `p` by `dx`.
+##
+## Returns the new x.
+def move(p: ptr Point, dx: int): int {
+	p.x = p.x + dx
+	return p.x
+}
+
+extern puts(s: ptr any): int32
+## stray doc
+";
+
+/// Tests that the formatter keeps comments inside expressions and inside empty lists, and that its output is
+/// formatted the same again.
+#[test]
+fn test_ftl_comments_inside_expressions() {
+	let formatted = emit::<crate::emitter::Ftl>(
+		"struct Empty {
+		}
+
+		def idle(  # no arguments
+		) {
+		}
+
+		def main() {
+			var total: int = 1 + # first
+				2 * -  # negated
+				3
+			var done: bool = 1 == 2
+			var ok: bool = not # inverted
+				done
+			idle( # no parameters
+			)
+			var empty: Empty = new Empty(  # no fields
+			)
+			var answer: int = # start of the expression
+				42
+		}",
+	);
+	assert_eq!(
+		formatted,
+		"struct Empty {
+}
+def idle(# no arguments
+) {
+
+}
+def main() {
+var total: int = 1 + # first
+2 * - # negated
+3
+var done: bool = 1 == 2
+var ok: bool = not # inverted
+done
+idle(# no parameters
+)
+var empty: Empty = new Empty(# no fields
+)
+var answer: int = 42 # start of the expression
+
+}
+"
+	);
+	assert_eq!(emit::<crate::emitter::Ftl>(&formatted), formatted);
+}
+
+/// Type checks `source_code`, which must be free of errors, and returns the C code emitted for it without the
+/// runtime prelude.
+fn c(source_code: &str) -> String {
+	let output = emit::<crate::emitter::C>(source_code);
+	output.strip_prefix(RUNTIME).expect("C code starts with the runtime").trim_start_matches('\n').to_owned()
+}
+
+/// Boilerplate code for emitting source code with the emitter `E`. The source code must be free of errors.
+fn emit<E: Emitter>(source_code: &str) -> String {
+	let source = Arc::new(Source::new("testfile".to_owned(), source_code.to_owned()));
+	let (tokens, lexer_errors) = Lexer::new(&source).tokenize();
+	assert_eq!(lexer_errors, Vec::new());
+	let (nodes, parser_errors) = Parser::new(tokens.into_iter(), source.clone()).parse();
+	assert_eq!(parser_errors, Vec::new());
+	let symbol_table = SymbolTable::global_symbol_scan(nodes.iter().map(|node| &node.node)).unwrap();
+	TypeChecker::type_check(symbol_table, nodes.iter().map(|node| &node.node)).unwrap();
+
+	let output = Output::default();
+	E::codegen(nodes.into_iter(), Box::new(output.clone())).unwrap();
+	let bytes = output.0.take();
+	String::from_utf8(bytes).unwrap()
+}
+
+/// Writer collecting the emitted code, which is still accessible after the emitter took ownership of a clone.
+#[derive(Clone, Default)]
+struct Output(Rc<RefCell<Vec<u8>>>);
+
+impl io::Write for Output {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.0.borrow_mut().write(buf)
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
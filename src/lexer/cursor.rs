@@ -0,0 +1,57 @@
+/// Cursor over the chars of a borrowed source code, tracking the current byte offset.
+#[derive(Clone, Debug)]
+pub(super) struct Cursor<'a> {
+	/// The whole source code.
+	text: &'a str,
+	/// Byte offset of the next char.
+	offset: usize,
+}
+
+impl<'a> Cursor<'a> {
+	pub(super) fn new(text: &'a str) -> Self {
+		Self { text, offset: 0 }
+	}
+
+	/// Returns the byte offset of the next char.
+	pub(super) fn offset(&self) -> usize {
+		self.offset
+	}
+
+	/// Returns the next char without consuming it.
+	pub(super) fn peek(&self) -> Option<char> {
+		self.text[self.offset..].chars().next()
+	}
+
+	/// Returns the char after the next char without consuming anything.
+	pub(super) fn peek_second(&self) -> Option<char> {
+		self.text[self.offset..].chars().nth(1)
+	}
+
+	/// Consumes and returns the next char.
+	pub(super) fn bump(&mut self) -> Option<char> {
+		let char_ = self.peek()?;
+		self.offset += char_.len_utf8();
+		Some(char_)
+	}
+
+	/// Consumes the next char if it is `expected`.
+	pub(super) fn eat(&mut self, expected: char) -> bool {
+		let matches = self.peek() == Some(expected);
+		if matches {
+			self.offset += expected.len_utf8();
+		}
+		matches
+	}
+
+	/// Consumes chars as long as `predicate` holds.
+	pub(super) fn eat_while(&mut self, mut predicate: impl FnMut(char) -> bool) {
+		while self.peek().is_some_and(&mut predicate) {
+			self.bump();
+		}
+	}
+
+	/// Returns the source code from the byte offset `start` up to the current offset.
+	pub(super) fn slice_from(&self, start: usize) -> &'a str {
+		&self.text[start..self.offset]
+	}
+}
@@ -1,14 +1,60 @@
+use std::fmt;
+
 use thiserror::Error;
 
-use crate::source::{PositionContainer, Symbol};
+use crate::source::{PositionContainer, SourcePositionRange};
 
 /// Lexer errors.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Error)]
 pub enum Error {
 	#[error("Unknown symbol {0}")]
-	UnknownSymbol(Symbol),
+	UnknownSymbol(PositionContainer<char>),
 	#[error("Illegal symbol {}", .0.as_ref().map(|s| s.to_string()).unwrap_or("None".to_owned()))]
-	IllegalSymbol(Option<Symbol>),
-	#[error("Could not parse number {0}")]
-	ParseNumberError(PositionContainer<String>),
+	IllegalSymbol(Option<PositionContainer<char>>),
+	/// A malformed number literal. `number` is the offending part of the literal.
+	#[error("Could not parse number: {reason} {number}")]
+	ParseNumberError { number: PositionContainer<String>, reason: &'static str },
+	/// A string or char literal without closing quote. Contains the literal up to the end of the line.
+	#[error("Unterminated literal {0}")]
+	UnterminatedLiteral(PositionContainer<String>),
+	/// A block comment without closing `]#`. Contains the opening `#[`.
+	#[error("Unterminated block comment {0}")]
+	UnterminatedBlockComment(PositionContainer<String>),
+	/// An unknown or malformed escape sequence in a string or char literal.
+	#[error("Invalid escape sequence {0}")]
+	InvalidEscape(PositionContainer<String>),
+	/// A char literal that does not contain exactly one char.
+	#[error("Char literal must contain exactly one char {0}")]
+	InvalidCharLiteral(PositionContainer<String>),
+}
+
+impl Error {
+	/// Returns the position of the offending source code, if known.
+	pub fn position(&self) -> Option<&SourcePositionRange> {
+		match self {
+			Error::UnknownSymbol(symbol) => Some(&symbol.position),
+			Error::IllegalSymbol(symbol) => symbol.as_ref().map(|symbol| &symbol.position),
+			Error::ParseNumberError { number, .. } => Some(&number.position),
+			Error::UnterminatedLiteral(literal) => Some(&literal.position),
+			Error::UnterminatedBlockComment(opener) => Some(&opener.position),
+			Error::InvalidEscape(escape) => Some(&escape.position),
+			Error::InvalidCharLiteral(literal) => Some(&literal.position),
+		}
+	}
+}
+
+/// All [`Error`]s found while [tokenizing](crate::lexer::Lexer::tokenize) a source file.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Error)]
+pub struct Errors(pub Vec<Error>);
+
+impl fmt::Display for Errors {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for (i, error) in self.0.iter().enumerate() {
+			if i != 0 {
+				writeln!(f)?;
+			}
+			write!(f, "{}", error)?;
+		}
+		Ok(())
+	}
 }
@@ -1,16 +1,18 @@
 //! Splitting the source code into [`Token`]s.
 
+mod cursor;
 mod error;
 #[cfg(test)]
 mod test;
 
-use std::{iter::Peekable, ops::Deref};
+use std::{ops::Range, sync::Arc};
 
-pub use error::Error;
+pub use error::{Error, Errors};
 
 use crate::{
-	source::{PositionContainer, Symbol},
-	token::{Token, TokenKind},
+	lexer::cursor::Cursor,
+	source::{PositionContainer, Source, Span},
+	token::{NumberSuffix, Token, TokenKind},
 };
 
 /// [`Token`] or [`lexer::Error`](Error).
@@ -21,253 +23,648 @@ pub type LexResult = Result<Token, Error>;
 /// A lexer is the first phase of a compiler. It analyses the text of the sourcecode and builds
 /// [`Token`]s, like [`Identifier("foo")`](TokenKind::Identifier), [`Number(42)`](TokenKind::Float) or [`TokenKind::Plus`]. The lexer is not aware of the meaning of
 /// the tokens; it just builds them.
-pub struct Lexer<T>
-where
-	T: Iterator<Item = Symbol>,
-{
-	/// Iterator over [`Symbol`]s of the source code.
-	symbols: Peekable<T>,
+///
+/// The source code is read through a [`Cursor`] over the borrowed text, and tokens only store a [`Span`] into it.
+/// Only errors are located with line and column, since they need to be reported to the user.
+///
+/// A newline ends a statement and is emitted as [`TokenKind::EndOfLine`], unless the statement is obviously
+/// incomplete: Newlines inside parentheses or brackets, after an operator, `=`, `,`, `:`, `.`, `..`, a keyword like
+/// `var` or `{`, and consecutive newlines are skipped. This allows to continue long expressions on the next line,
+/// e.g. after a trailing `+`, while `a = b` followed by `(c)` on the next line are two statements. Newlines between
+/// `}` and `else` are skipped as well, so that `else` may start the line after the block of an `if`.
+pub struct Lexer<'a> {
+	/// The source code, used for locating errors.
+	source: &'a Arc<Source>,
+	/// Cursor over the chars of the [source code](Self::source).
+	cursor: Cursor<'a>,
+	/// Number of currently open parentheses and brackets.
+	paren_depth: usize,
+	/// Whether the statement continues after a newline, since the last token cannot end a statement.
+	continues_line: bool,
+	/// Whether the last token is `}`, after which the statement continues if the next line starts with `else`.
+	closes_block: bool,
 }
 
-impl<T> Lexer<T>
-where
-	T: Iterator<Item = Symbol>,
-{
-	/// Creates a [`Lexer`] from the given [`Symbol`] iterator.
-	pub fn new(symbols: T) -> Self {
-		Self { symbols: symbols.peekable() }
+impl<'a> Lexer<'a> {
+	/// Creates a [`Lexer`] for the given [`Source`].
+	pub fn new(source: &'a Arc<Source>) -> Self {
+		// Leading newlines do not end a statement
+		Self { source, cursor: Cursor::new(&source.text), paren_depth: 0, continues_line: true, closes_block: false }
 	}
 
-	/// Checks whether [`Self::symbols`] is going to yield a whitespace next.
+	/// Tokenizes the whole source code, recovering from errors.
 	///
-	/// This is used to skip irrelevant symbols. If [`Self::symbols`] is going to yield [`None`],
-	/// `false` is returned. This prevents [`Self::skip_whitespaces()`] from running into an infinite loop.
-	fn on_whitespace(&mut self) -> bool {
-		self.symbols.peek().map(|symbol| symbol.is_whitespace()).unwrap_or(false)
+	/// Lexing does not stop at the first [`Error`]. Instead, each error is collected and a [`TokenKind::Error`]
+	/// token takes the place of the offending source code, so that all lexical errors of a file can be reported
+	/// at once.
+	pub fn tokenize(mut self) -> (Vec<Token>, Vec<Error>) {
+		let mut tokens = Vec::new();
+		let mut errors = Vec::new();
+		loop {
+			self.skip_whitespaces();
+			let start = self.cursor.offset();
+			let Some(lex_result) = self.tokenize_next_item() else {
+				break;
+			};
+			match lex_result {
+				Ok(token) => tokens.push(token),
+				Err(error) => {
+					tokens.push(Token::new(TokenKind::Error, self.span_from(start)));
+					errors.push(error);
+				},
+			}
+		}
+		(tokens, errors)
 	}
 
-	/// Skips all whitespace symbols until the first "normal" (non-whitespace) symbol is found.
+	/// Skips all whitespace chars until the first "normal" (non-whitespace) char is found.
+	///
+	/// A newline that ends a statement is not skipped, so that it is emitted as [`TokenKind::EndOfLine`].
 	fn skip_whitespaces(&mut self) {
-		while self.on_whitespace() {
-			self.symbols.next();
-		}
+		let skip_newlines = self.paren_depth > 0 || self.continues_line || (self.closes_block && self.else_follows());
+		self.cursor.eat_while(|char_| char_.is_whitespace() && (skip_newlines || char_ != '\n'));
 	}
 
-	/// Tokenizes the next symbol from [`Self::symbols`]. Returns [`None`] if [`Self::symbols`] is drained.
-	fn tokenize_next_item(&mut self) -> Option<LexResult> {
-		self.skip_whitespaces();
-		// Returns `None` if `self.symbols` is drained
-		let symbol = self.symbols.peek()?.clone();
+	/// Checks whether the next word after whitespaces and newlines is the keyword `else`.
+	fn else_follows(&self) -> bool {
+		let rest = self.source.text[self.cursor.offset()..].trim_start();
+		rest.strip_prefix("else")
+			.is_some_and(|after| !after.starts_with(|char_: char| char_.is_alphanumeric() || char_ == '_'))
+	}
 
-		let token = match symbol {
-			symbol if symbol.is_alphabetic() => {
-				let read_string = self.read_string();
-				parse_string(read_string)
-			},
-			symbol if symbol.is_numeric() => {
-				let number = self.read_number();
-				parse_number(number)
-			},
-			symbol if is_comment(*symbol) => {
-				let comment = self.read_comment();
-				Ok(Token::new(TokenKind::Comment((*comment).clone()), comment.position))
-			},
-			symbol if *symbol == '"' => {
-				let string = self.read_string_literal();
-				Ok(Token::new(TokenKind::StringLiteral(string.value), string.position))
-			},
-			/*symbol if symbol == '\n' => {
+	/// Tokenizes the next char(s) after skipped whitespaces. Returns [`None`] if the source code is drained.
+	fn tokenize_next_item(&mut self) -> Option<LexResult> {
+		let start = self.cursor.offset();
+		// Returns `None` if the source code is drained
+		let token = match self.cursor.peek()? {
+			char_ if char_.is_alphabetic() || char_ == '_' => Ok(self.read_string()),
+			char_ if char_.is_ascii_digit() => self.read_number(),
+			char_ if is_comment(char_) && self.at(BLOCK_COMMENT_START) => self.read_block_comment(),
+			char_ if is_comment(char_) => Ok(self.read_comment()),
+			'"' => self.read_string_literal(),
+			'\'' => self.read_char_literal(),
+			'\n' => {
 				// Consume newline
-				assert_eq!(self.letters.next().map(&|(_, letter)| letter), Some('\n'));
-				Ok(Token {
-					kind: TokenKind::EndOfLine,
-					position: Position::from_start_len(position, letter.len_utf8()),
-				})
-			}*/
-			symbol if is_special_char(*symbol) => self.read_special(),
+				self.cursor.bump();
+				Ok(Token::new(TokenKind::EndOfLine, self.span_from(start)))
+			},
+			char_ if is_special_char(char_) => self.read_special(),
 			_ => {
-				// Consume unknown symbol
-				self.symbols.next();
-				Err(Error::UnknownSymbol(symbol))
+				// Consume unknown char
+				self.cursor.bump();
+				Err(Error::UnknownSymbol(self.symbol(start)))
 			},
 		};
+		match &token {
+			Ok(token) => self.track_line_continuation(token),
+			// Let the next newline end the erroneous statement
+			Err(_) => {
+				self.continues_line = false;
+				self.closes_block = false;
+			},
+		}
 		Some(token)
 	}
 
+	/// Updates [`Self::paren_depth`] and [`Self::continues_line`] after `token` was read.
+	fn track_line_continuation(&mut self, token: &Token) {
+		match token.value {
+			// Comments do not affect whether the statement continues
+			TokenKind::Comment(_) | TokenKind::DocComment(_) => return,
+			TokenKind::OpeningParentheses | TokenKind::OpeningSquareBrackets => self.paren_depth += 1,
+			TokenKind::ClosingParentheses | TokenKind::ClosingSquareBrackets => {
+				self.paren_depth = self.paren_depth.saturating_sub(1)
+			},
+			_ => (),
+		}
+		self.continues_line = continues_line(&token.value);
+		self.closes_block = token.value == TokenKind::ClosingCurlyBraces;
+	}
+
+	/// Returns the [`Span`] from the byte offset `start` up to the current offset.
+	fn span_from(&self, start: usize) -> Span {
+		Span::new(start, self.cursor.offset())
+	}
+
+	/// Returns the source code in the byte range `range` together with its position, e.g. for errors.
+	fn lexeme(&self, range: Range<usize>) -> PositionContainer<String> {
+		let span = Span::new(range.start, range.end);
+		PositionContainer::new(self.source.text[range].to_owned(), self.source.locate(span))
+	}
+
+	/// Returns the char at byte offset `start` together with its position.
+	fn symbol(&self, start: usize) -> PositionContainer<char> {
+		let char_ = self.source.text[start..].chars().next().unwrap();
+		let lexeme = self.lexeme(start..start + char_.len_utf8());
+		PositionContainer::new(char_, lexeme.position)
+	}
+
 	/// Reads a string literal, i.e. something enclosed by `"`, while also taking care of escaping.
-	fn read_string_literal(&mut self) -> PositionContainer<String> {
-		// Discard starting quotes
-		let starting_quotes = self.symbols.next().unwrap();
-		assert_eq!(starting_quotes.value, '"');
+	///
+	/// String literals end at the closing `"` and must not span multiple lines.
+	fn read_string_literal(&mut self) -> LexResult {
+		let start = self.cursor.offset();
+		let content = self.read_quoted('"')?;
+		Ok(Token::new(TokenKind::StringLiteral(content), self.span_from(start)))
+	}
+
+	/// Reads a char literal, i.e. a single (possibly escaped) char enclosed by `'`.
+	fn read_char_literal(&mut self) -> LexResult {
+		let start = self.cursor.offset();
+		let content = self.read_quoted('\'')?;
+		let mut chars = content.chars();
+		match (chars.next(), chars.next()) {
+			(Some(char_), None) => Ok(Token::new(TokenKind::CharLiteral(char_), self.span_from(start))),
+			_ => Err(Error::InvalidCharLiteral(self.lexeme(start..self.cursor.offset()))),
+		}
+	}
 
-		let mut position = starting_quotes.position.clone();
-		let mut string = String::new();
+	/// Reads a literal enclosed by `quote` and returns its content with resolved escape sequences.
+	///
+	/// If the literal contains an invalid escape sequence, it is still read up to the closing quote, so that lexing
+	/// can continue after it.
+	fn read_quoted(&mut self, quote: char) -> Result<String, Error> {
+		let start = self.cursor.offset();
+		assert!(self.cursor.eat(quote));
 
-		while let Some(mut symbol) = self.symbols.peek().cloned() {
-			if *symbol == '"' {
-				break
+		let mut content = String::new();
+		let mut error = None;
+		loop {
+			match self.cursor.peek() {
+				// Literal not closed until the end of line or file
+				None | Some('\n') => return Err(Error::UnterminatedLiteral(self.lexeme(start..self.cursor.offset()))),
+				Some(char_) if char_ == quote => {
+					self.cursor.bump();
+					break;
+				},
+				Some('\\') => match self.read_escape() {
+					Ok(char_) => content.push(char_),
+					Err(err) => {
+						error.get_or_insert(err);
+					},
+				},
+				Some(char_) => {
+					self.cursor.bump();
+					content.push(char_);
+				},
 			}
+		}
+		match error {
+			Some(error) => Err(error),
+			None => Ok(content),
+		}
+	}
 
-			// Escaping
-			if *symbol == '\\' {
-				// Read next escaped character
-				self.symbols.next();
-				symbol = match self.symbols.peek() {
-					Some(symbol) => symbol.clone(),
-					None => break
-				};
-
-				symbol.value = match symbol.value {
-					'n' => '\n',
-					'r' => '\r',
-					symbol => symbol,
+	/// Reads an escape sequence like `\n`, `\x41` or `\u{1F600}`, starting at its `\`.
+	fn read_escape(&mut self) -> Result<char, Error> {
+		let start = self.cursor.offset();
+		assert!(self.cursor.eat('\\'));
+		let invalid_escape = |lexer: &Self| Error::InvalidEscape(lexer.lexeme(start..lexer.cursor.offset()));
+
+		// Leave a newline for the caller, to report the unterminated literal
+		let escaped = match self.cursor.peek() {
+			Some(char_) if char_ != '\n' => self.cursor.bump().unwrap(),
+			_ => return Err(invalid_escape(self)),
+		};
+		match escaped {
+			'n' => Ok('\n'),
+			'r' => Ok('\r'),
+			't' => Ok('\t'),
+			'0' => Ok('\0'),
+			'\\' => Ok('\\'),
+			'"' => Ok('"'),
+			'\'' => Ok('\''),
+			// ASCII char given by exactly two hex digits, e.g. `\x41`
+			'x' => {
+				let digits = self.read_hex_digits(2);
+				match u8::from_str_radix(digits, 16) {
+					Ok(byte) if digits.len() == 2 && byte.is_ascii() => Ok(char::from(byte)),
+					_ => Err(invalid_escape(self)),
 				}
-			}
+			},
+			// Unicode scalar value given by up to six hex digits, e.g. `\u{1F600}`
+			'u' => {
+				if !self.cursor.eat('{') {
+					return Err(invalid_escape(self));
+				}
+				let digits = self.read_hex_digits(6);
+				if !self.cursor.eat('}') {
+					return Err(invalid_escape(self));
+				}
+				u32::from_str_radix(digits, 16).ok().and_then(char::from_u32).ok_or_else(|| invalid_escape(self))
+			},
+			_ => Err(invalid_escape(self)),
+		}
+	}
 
-			string.push(*symbol);
-			position.position.end = symbol.position.position.end;
-			self.symbols.next();
+	/// Reads and returns up to `max` hex digits.
+	fn read_hex_digits(&mut self, max: usize) -> &'a str {
+		let start = self.cursor.offset();
+		// Hex digits are ASCII, so the number of bytes equals the number of digits
+		while self.cursor.offset() - start < max && self.cursor.peek().is_some_and(|char_| char_.is_ascii_hexdigit()) {
+			self.cursor.bump();
 		}
-		PositionContainer::new(string, position)
+		self.cursor.slice_from(start)
+	}
+
+	/// Reads an identifier or keyword.
+	fn read_string(&mut self) -> Token {
+		let start = self.cursor.offset();
+		self.cursor.eat_while(|char_| char_.is_alphanumeric() || char_ == '_');
+		Token::new(parse_string(self.cursor.slice_from(start)), self.span_from(start))
 	}
 
-	/// Reads a string from [`Self::symbols`].
-	fn read_string(&mut self) -> PositionContainer<String> {
-		let mut string = String::new();
-		let mut position = self.symbols.peek().unwrap().position.clone();
-		while let Some(symbol) = self.symbols.peek().cloned() {
-			let is_string_char = symbol.is_alphanumeric() || *symbol == '_';
-			if !is_string_char {
+	/// Reads a number literal, including its prefix, exponent and suffix, and [parses](Self::parse_number) it.
+	fn read_number(&mut self) -> LexResult {
+		let start = self.cursor.offset();
+		while let Some(char_) = self.cursor.peek() {
+			let number = self.cursor.slice_from(start);
+			// Only a `.` followed by a digit is a decimal point. Otherwise, it is a range like in `0..n` or a field
+			// access like in `people@0.age`.
+			let is_decimal_point = char_ == '.' && self.cursor.peek_second().is_some_and(|next| next.is_ascii_digit());
+			let is_number_char = char_.is_alphanumeric() || char_ == '_' || is_decimal_point;
+			// Sign of an exponent like in `1.5e-3`. In hex literals, `e` is a digit and not an exponent.
+			let is_exponent_sign = (char_ == '+' || char_ == '-')
+				&& number.ends_with(['e', 'E'])
+				&& !(number.starts_with("0x") || number.starts_with("0X"));
+			if !is_number_char && !is_exponent_sign {
 				break;
 			}
-			string.push(*symbol);
-			position.position.end = symbol.position.position.end;
-			self.symbols.next();
+			self.cursor.bump();
 		}
-		PositionContainer::new(string, position)
+		self.parse_number(start)
 	}
 
-	/// Reads a number from [`Self::symbols`].
-	fn read_number(&mut self) -> PositionContainer<String> {
-		let mut number = String::new();
-		let mut position = self.symbols.peek().unwrap().position.clone();
-		while let Some(symbol) = self.symbols.peek().cloned() {
-			let is_number_char = symbol.is_numeric() || *symbol == '.';
-			if !is_number_char {
-				break;
+	/// Parses the number literal starting at byte offset `start` to a [`TokenKind::Int`] or [`TokenKind::Float`].
+	///
+	/// Supported are the prefixes `0x`, `0b` and `0o`, `_` as digit separator, exponents like in `1.5e-3` and
+	/// [type suffixes](NumberSuffix) like in `42u8`. For malformed literals, the returned [`Error::ParseNumberError`]
+	/// points at the offending part of the literal.
+	///
+	/// Integer literals with suffix may be as large as the [magnitude of the minimum](NumberSuffix::max_int) of their
+	/// type, so that negated minimums like `-128i8` can be written. The
+	/// [`TypeChecker`](crate::semantic_analyzer::TypeChecker) makes sure that such literals are actually negated.
+	/// Integer literals without suffix take the type expected where they are used, so their range is checked by the
+	/// type checker as well.
+	fn parse_number(&self, start: usize) -> LexResult {
+		let number = self.cursor.slice_from(start);
+		// Prefix, digits and exponent are ASCII, so bytes can be used as chars. Only the suffix may contain other chars.
+		let bytes = number.as_bytes();
+		// Error pointing at the byte range `range` of the literal
+		let number_error = |range: Range<usize>, reason| Error::ParseNumberError {
+			number: self.lexeme(start + range.start..start + range.end),
+			reason,
+		};
+
+		let radix = match bytes.get(..2) {
+			Some([b'0', b'x' | b'X']) => 16,
+			Some([b'0', b'b' | b'B']) => 2,
+			Some([b'0', b'o' | b'O']) => 8,
+			_ => 10,
+		};
+		let prefix_len = if radix == 10 { 0 } else { 2 };
+
+		// Find the end of the digits, i.e. the start of the suffix
+		let skip_digits = |mut index: usize| {
+			while bytes.get(index).is_some_and(|byte| char::from(*byte).is_digit(radix) || *byte == b'_') {
+				index += 1;
 			}
-			number.push(*symbol);
-			position.position.end = symbol.position.position.end;
-			self.symbols.next();
+			index
+		};
+		let mut end = skip_digits(prefix_len);
+		let mut is_float = false;
+		if radix == 10 {
+			if bytes.get(end) == Some(&b'.') {
+				is_float = true;
+				end = skip_digits(end + 1);
+			}
+			if matches!(bytes.get(end), Some(b'e' | b'E')) {
+				is_float = true;
+				let exponent_start = end;
+				end += 1;
+				if matches!(bytes.get(end), Some(b'+' | b'-')) {
+					end += 1;
+				}
+				let exponent_digits_start = end;
+				end = skip_digits(end);
+				if end == exponent_digits_start {
+					return Err(number_error(exponent_start..end, "exponent without digits"));
+				}
+			}
+		}
+
+		let digits: String = number[prefix_len..end].chars().filter(|char_| *char_ != '_').collect();
+		if digits.is_empty() {
+			return Err(number_error(0..end, "missing digits"));
+		}
+
+		let suffix = match &number[end..] {
+			"" => None,
+			suffix => {
+				Some(NumberSuffix::try_from(suffix).map_err(|_| number_error(end..number.len(), "invalid suffix"))?)
+			},
+		};
+
+		let span = self.span_from(start);
+		if is_float || suffix.is_some_and(|suffix| suffix.is_float()) {
+			if suffix.is_some_and(|suffix| !suffix.is_float()) {
+				return Err(number_error(end..number.len(), "integer suffix for floating point number"));
+			}
+			if radix != 10 {
+				return Err(number_error(0..prefix_len, "floating point number with prefix"));
+			}
+			let float: f64 = digits.parse().map_err(|_| number_error(0..end, "invalid floating point number"))?;
+			if !float.is_finite() {
+				return Err(number_error(0..number.len(), "out of range for floating point numbers"));
+			}
+			Ok(Token::new(TokenKind::Float(float, suffix), span))
+		} else {
+			let int = u64::from_str_radix(&digits, radix).map_err(|_| number_error(0..end, "too large"))?;
+			if suffix.is_some_and(|suffix| int > suffix.max_int()) {
+				return Err(number_error(0..number.len(), "out of range for its type"));
+			}
+			Ok(Token::new(TokenKind::Int(int, suffix), span))
 		}
-		PositionContainer::new(number, position)
 	}
 
-	/// Reads a special character from [`Self::symbols`], e.g. operators and parenthesis.
+	/// Reads a special character, e.g. operators and parenthesis.
 	fn read_special(&mut self) -> LexResult {
-		let symbol = self.symbols.next().unwrap();
-		let position = symbol.position.clone();
-		match *symbol {
-			'+' => Ok(Token::new(TokenKind::Plus, position)),
-			'-' => Ok(Token::new(TokenKind::Minus, position)),
-			'*' => Ok(Token::new(TokenKind::Star, position)),
-			',' => Ok(Token::new(TokenKind::Comma, position)),
-			'(' => Ok(Token::new(TokenKind::OpeningParentheses, position)),
-			')' => Ok(Token::new(TokenKind::ClosingParentheses, position)),
-			'{' => Ok(Token::new(TokenKind::OpeningCurlyBraces, position)),
-			'}' => Ok(Token::new(TokenKind::ClosingCurlyBraces, position)),
-			'<' => Ok(Token::new(TokenKind::Less, position)),
-			'>' => Ok(Token::new(TokenKind::Greater, position)),
-			'.' => Ok(Token::new(TokenKind::Dot, position)),
-			':' => Ok(Token::new(TokenKind::Colon, position)),
-			'/' => Ok(Token::new(TokenKind::Slash, position)),
-			';' => Ok(Token::new(TokenKind::Semicolon, position)),
-			'[' => Ok(Token::new(TokenKind::OpeningSquareBrackets, position)),
-			']' => Ok(Token::new(TokenKind::ClosingSquareBrackets, position)),
+		let start = self.cursor.offset();
+		let kind = match self.cursor.bump().unwrap() {
+			'+' => TokenKind::Plus,
+			'-' => TokenKind::Minus,
+			'*' => TokenKind::Star,
+			'%' => TokenKind::Modulus,
+			',' => TokenKind::Comma,
+			'(' => TokenKind::OpeningParentheses,
+			')' => TokenKind::ClosingParentheses,
+			'{' => TokenKind::OpeningCurlyBraces,
+			'}' => TokenKind::ClosingCurlyBraces,
+			'<' => self.read_with_equal(TokenKind::Less, TokenKind::LessEqual),
+			'>' => self.read_with_equal(TokenKind::Greater, TokenKind::GreaterEqual),
+			'.' => match self.cursor.eat('.') {
+				// Read token is `..` or `..=`, i.e. a range
+				true => self.read_with_equal(TokenKind::DoubleDot, TokenKind::DoubleDotEqual),
+				false => TokenKind::Dot,
+			},
+			':' => TokenKind::Colon,
+			'/' => TokenKind::Slash,
+			';' => TokenKind::Semicolon,
+			'@' => TokenKind::At,
+			'[' => TokenKind::OpeningSquareBrackets,
+			']' => TokenKind::ClosingSquareBrackets,
 			'=' => {
-				match self.symbols.peek() {
+				if self.cursor.eat('=') {
+					// Read token is `==`, i.e. equal
+					TokenKind::DoubleEqual
+				} else if self.cursor.eat('/') {
 					// Read token is `=/` so far
-					Some(symbol) if **symbol == '/' => self.symbols.next(),
+					let next = self.cursor.offset();
+					match self.cursor.bump() {
+						// Read token is `=/=`, i.e. not equal
+						Some('=') => TokenKind::NotEqual,
+						// Illegal token `=/...`
+						Some(_) => return Err(Error::IllegalSymbol(Some(self.symbol(next)))),
+						None => return Err(Error::IllegalSymbol(None)),
+					}
+				} else {
 					// Ok, only a single `=` as token
-					_ => return Ok(Token::new(TokenKind::Equal, position)),
-				};
-				match self.symbols.next() {
-					// Read token is `=/=`, i.e. not equal
-					Some(symbol) if *symbol == '=' => Ok(Token::new(TokenKind::NotEqual, position)),
-					// Illegal token `=/...`
-					symbol => Err(Error::IllegalSymbol(symbol))?,
+					TokenKind::Equal
 				}
 			},
-			_ => Err(Error::IllegalSymbol(Some(symbol))),
+			_ => return Err(Error::IllegalSymbol(Some(self.symbol(start)))),
+		};
+		Ok(Token::new(kind, self.span_from(start)))
+	}
+
+	/// Reads an optional `=` following an already consumed char, e.g. for `<` and `<=`.
+	///
+	/// Returns `with_equal` if the next char is `=`, and `without_equal` otherwise.
+	fn read_with_equal(&mut self, without_equal: TokenKind, with_equal: TokenKind) -> TokenKind {
+		match self.cursor.eat('=') {
+			true => with_equal,
+			false => without_equal,
 		}
 	}
 
-	/// Reads a comment and returns its content.
-	fn read_comment(&mut self) -> PositionContainer<String> {
+	/// Reads a comment. Consecutive comment lines are merged into a single comment.
+	///
+	/// A comment starting with `##` is a [doc comment](TokenKind::DocComment), which is only merged with following doc
+	/// comment lines. The `##` and one following space are removed from each of its lines.
+	fn read_comment(&mut self) -> Token {
+		let start = self.cursor.offset();
+		let is_doc = self.at_doc_comment();
 		// Skip comment symbol
-		let mut postion = self.symbols.next().unwrap().position;
+		self.cursor.bump();
+		// Only comments on their own line are merged with the comments on the following lines, so that a trailing
+		// comment does not swallow the comment on the next line
+		let own_line = self.source.text[..start].rsplit('\n').next().is_some_and(|line| line.trim().is_empty());
 
 		let mut comment = String::new();
-		// Read letters and save them into comment
-		loop {
-			match self.symbols.peek() {
-				Some(symbol) if **symbol == '\n' => {
-					// Detected newline. Check if the next line is also a comment. If yes, continue parsing the next line
-					self.symbols.next(); // Consume \n
-					self.skip_whitespaces(); // Skip possible leading whitespaces
-					match self.symbols.peek() {
-						Some(symbol) if is_comment(**symbol) => (), // Is comment. Continue parsing
-						_ => break,                                 // Either none or not a comment. End parsing
-					};
+		let end = loop {
+			if is_doc {
+				// Skip the second `#` of `##`
+				self.cursor.bump();
+			}
+			// Read the rest of the line into the comment
+			let line_start = self.cursor.offset();
+			self.cursor.eat_while(|char_| char_ != '\n');
+			let end = self.cursor.offset();
+			comment.push_str(self.cursor.slice_from(line_start));
+
+			// Detected newline. Check if the next line is also a comment. If yes, continue parsing the next line
+			let before_newline = self.cursor.clone();
+			if !own_line || !self.cursor.eat('\n') {
+				// File read to end
+				break end;
+			}
+			self.cursor.eat_while(char::is_whitespace); // Skip possible leading whitespaces
+			match self.cursor.peek() {
+				// Is comment of the same kind. Continue parsing
+				Some(char_)
+					if is_comment(char_) && !self.at(BLOCK_COMMENT_START) && self.at_doc_comment() == is_doc =>
+				{
 					comment.push('\n');
+					if is_doc {
+						// Skip the first `#` of `##`
+						self.cursor.bump();
+					}
 				},
-				// Push next letter to comment
-				Some(symbol) => {
-					comment.push(**symbol);
-					postion.position.end = symbol.position.position.end;
-					self.symbols.next();
+				_ => {
+					// Either none or not a comment. End parsing, but leave the newline, since it may end a statement
+					self.cursor = before_newline;
+					break end;
 				},
-				// File read to end
-				None => break,
 			}
+		};
+		let span = Span::new(start, end);
+		if is_doc {
+			let lines: Vec<&str> =
+				comment.lines().map(|line| line.strip_prefix(' ').unwrap_or(line).trim_end()).collect();
+			return Token::new(TokenKind::DocComment(lines.join("\n").trim().to_owned()), span);
 		}
 		// Remove potential trailing whitespaces
-		comment = comment.trim().to_owned();
-		PositionContainer::new(comment, postion)
+		Token::new(TokenKind::Comment(comment.trim().to_owned()), span)
+	}
+
+	/// Reads a block comment enclosed by `#[` and `]#`. Block comments can be nested.
+	///
+	/// The text of the comment is the whole block comment, including `#[` and `]#`.
+	fn read_block_comment(&mut self) -> LexResult {
+		let start = self.cursor.offset();
+		let mut depth: usize = 0;
+		loop {
+			if self.at(BLOCK_COMMENT_START) {
+				depth += 1;
+			} else if self.at(BLOCK_COMMENT_END) {
+				depth -= 1;
+			} else if self.cursor.bump().is_some() {
+				continue;
+			} else {
+				// Report the opener, since the rest of the file is swallowed by the comment
+				let opener = start..start + BLOCK_COMMENT_START.len();
+				return Err(Error::UnterminatedBlockComment(self.lexeme(opener)));
+			}
+			// Skip the two chars of `#[` or `]#`
+			self.cursor.bump();
+			self.cursor.bump();
+			if depth == 0 {
+				let span = self.span_from(start);
+				return Ok(Token::new(TokenKind::Comment(self.cursor.slice_from(start).to_owned()), span));
+			}
+		}
+	}
+
+	/// Checks whether a [doc comment](TokenKind::DocComment) starts at the current offset.
+	fn at_doc_comment(&self) -> bool {
+		self.at("##")
+	}
+
+	/// Checks whether the source code at the current offset starts with `prefix`.
+	fn at(&self, prefix: &str) -> bool {
+		self.source.text[self.cursor.offset()..].starts_with(prefix)
 	}
 }
 
-/// Parses a string to a keyword (`def`, `if`, `else`, ...), or to a [`TokenKind::Identifier`] otherwise.
-fn parse_string(string: PositionContainer<String>) -> LexResult {
-	Ok(match string.as_str() {
-		"def" => Token::new(TokenKind::Def, string.position),
-		"extern" => Token::new(TokenKind::Extern, string.position),
-		"bitor" => Token::new(TokenKind::BitOr, string.position),
-		"bitand" => Token::new(TokenKind::BitAnd, string.position),
-		"mod" => Token::new(TokenKind::Modulus, string.position),
-		"if" => Token::new(TokenKind::If, string.position),
-		"else" => Token::new(TokenKind::Else, string.position),
-		"while" => Token::new(TokenKind::While, string.position),
-		"ptr" => Token::new(TokenKind::Pointer, string.position),
-		"struct" => Token::new(TokenKind::Struct, string.position),
-		"var" => Token::new(TokenKind::Var, string.position),
-		"return" => Token::new(TokenKind::Return, string.position),
-		_ => Token::new(TokenKind::Identifier(string.deref().to_owned()), string.position),
-	})
+/// Words that are lexed as [`TokenKind::Identifier`], but must not be used as names, since they could become
+/// keywords in the future. See the list of reserved keywords in the README.
+pub const RESERVED_KEYWORDS: &[&str] = &[
+	"default", // Memory
+	"bool", "true", "false", // Logic
+	"const", "string", "list", "enum", // Data structures
+	"debug", "print", "error", // Useful stuff
+	"int8", "uint8", "int16", "uint16", "int32", "uint32", "int64", "uint64", // Integer data types
+	"float32", "float64", // Floating point number data types
+	"nothing", "any", // Special data types
+];
+
+/// Checks whether `word` is one of the [`RESERVED_KEYWORDS`].
+pub fn is_reserved_keyword(word: &str) -> bool {
+	RESERVED_KEYWORDS.contains(&word)
 }
 
-/// Parses a number to a [`TokenKind::Float`].
-fn parse_number(number_str: PositionContainer<String>) -> LexResult {
-	let is_float = number_str.contains('.');
-	if is_float {
-		let float: f64 = number_str.parse().map_err(|_| Error::ParseNumberError(number_str.clone()))?;
-		Ok(Token::new(TokenKind::Float(float), number_str.position))
-	} else {
-		let int: i64 = number_str.parse().map_err(|_| Error::ParseNumberError(number_str.clone()))?;
-		Ok(Token::new(TokenKind::Int(int), number_str.position))
+/// Parses a string to a keyword (`def`, `if`, `else`, ...), or to a [`TokenKind::Identifier`] otherwise.
+fn parse_string(string: &str) -> TokenKind {
+	match string {
+		"def" => TokenKind::Def,
+		"extern" => TokenKind::Extern,
+		"bitor" => TokenKind::BitOr,
+		"bitand" => TokenKind::BitAnd,
+		"bitxor" => TokenKind::BitXor,
+		"shl" => TokenKind::ShiftLeft,
+		"shr" => TokenKind::ShiftRight,
+		"bitnot" => TokenKind::BitNot,
+		"mod" => TokenKind::Modulus,
+		"and" => TokenKind::And,
+		"or" => TokenKind::Or,
+		"xor" => TokenKind::Xor,
+		"not" => TokenKind::Not,
+		"if" => TokenKind::If,
+		"else" => TokenKind::Else,
+		"while" => TokenKind::While,
+		"for" => TokenKind::For,
+		"in" => TokenKind::In,
+		"of" => TokenKind::Of,
+		"step" => TokenKind::Step,
+		"break" => TokenKind::Break,
+		"continue" => TokenKind::Continue,
+		"ptr" => TokenKind::Pointer,
+		"struct" => TokenKind::Struct,
+		"var" => TokenKind::Var,
+		"return" => TokenKind::Return,
+		"new" => TokenKind::New,
+		"arr" => TokenKind::Array,
+		"ref" => TokenKind::Reference,
+		"deref" => TokenKind::Dereference,
+		"alloc" => TokenKind::Alloc,
+		"del" => TokenKind::Delete,
+		"nil" => TokenKind::Nil,
+		_ => TokenKind::Identifier(string.to_owned()),
 	}
 }
 
+/// Checks whether a statement continues on the next line after a token of `kind`, i.e. whether the statement is
+/// incomplete.
+fn continues_line(kind: &TokenKind) -> bool {
+	matches!(
+		kind,
+		TokenKind::Plus
+			| TokenKind::Minus
+			| TokenKind::Star
+			| TokenKind::Slash
+			| TokenKind::Less
+			| TokenKind::LessEqual
+			| TokenKind::Greater
+			| TokenKind::GreaterEqual
+			| TokenKind::DoubleEqual
+			| TokenKind::NotEqual
+			| TokenKind::And
+			| TokenKind::Or
+			| TokenKind::Xor
+			| TokenKind::Not
+			| TokenKind::BitOr
+			| TokenKind::BitAnd
+			| TokenKind::BitXor
+			| TokenKind::BitNot
+			| TokenKind::ShiftLeft
+			| TokenKind::ShiftRight
+			| TokenKind::Modulus
+			| TokenKind::Equal
+			| TokenKind::Comma
+			| TokenKind::Colon
+			| TokenKind::Dot
+			| TokenKind::DoubleDot
+			| TokenKind::DoubleDotEqual
+			| TokenKind::Semicolon
+			| TokenKind::OpeningCurlyBraces
+			| TokenKind::Def
+			| TokenKind::Extern
+			| TokenKind::Struct
+			| TokenKind::Var
+			| TokenKind::New
+			| TokenKind::If
+			| TokenKind::Else
+			| TokenKind::While
+			| TokenKind::For
+			| TokenKind::In
+			| TokenKind::Of
+			| TokenKind::Step
+			| TokenKind::Pointer
+			| TokenKind::Array
+			| TokenKind::At
+			| TokenKind::Reference
+			| TokenKind::Dereference
+			| TokenKind::Alloc
+			| TokenKind::Delete
+			| TokenKind::EndOfLine
+	)
+}
+
+/// Opens a block comment.
+const BLOCK_COMMENT_START: &str = "#[";
+/// Closes a block comment.
+const BLOCK_COMMENT_END: &str = "]#";
+
 /// Checks whether `letter` is a letter that starts a comment line.
 fn is_comment(letter: char) -> bool {
 	letter == '#'
@@ -275,16 +672,14 @@ fn is_comment(letter: char) -> bool {
 
 /// Checks whether `letter` is a special character like `+`, `-`, `=`, `*`.
 fn is_special_char(letter: char) -> bool {
-	['+', '-', '=', '<', '*', '(', ')', '{', '}', '.', ':', ',', '/', ';', '[', ']'].contains(&letter)
+	['+', '-', '=', '<', '>', '*', '%', '(', ')', '{', '}', '.', ':', ',', '/', ';', '[', ']', '@'].contains(&letter)
 }
 
-impl<T> Iterator for Lexer<T>
-where
-	T: Iterator<Item = Symbol>,
-{
+impl Iterator for Lexer<'_> {
 	type Item = LexResult;
 
 	fn next(&mut self) -> Option<Self::Item> {
+		self.skip_whitespaces();
 		self.tokenize_next_item()
 	}
 }
@@ -1,41 +1,413 @@
-use std::sync::Arc;
 use crate::lexer::*;
 use crate::source::Source;
-use crate::token::Token;
-
+use crate::token::{NumberSuffix, Token};
+use std::sync::Arc;
 
 /// Tests that the lexer can read a string literal containing escaped quotes.
 #[test]
 fn test_read_string_literal() {
-    let tokens = lexer(r#""hello \"name\"!""#);
-    assert_eq!(tokens[0].value, TokenKind::StringLiteral(r#"hello "name"!"#.to_owned()));
+	let tokens = lexer(r#""hello \"name\"!""#);
+	assert_eq!(tokens[0].value, TokenKind::StringLiteral(r#"hello "name"!"#.to_owned()));
 }
 
 /// Tests that the lexer can read an identifier.
 #[test]
 fn test_read_identifier() {
-    let tokens = lexer("hello");
-    assert_eq!(tokens[0].value, TokenKind::Identifier("hello".to_owned()));
+	let tokens = lexer("hello");
+	assert_eq!(tokens[0].value, TokenKind::Identifier("hello".to_owned()));
+	let tokens = lexer("_len");
+	assert_eq!(tokens[0].value, TokenKind::Identifier("_len".to_owned()));
 }
 
 /// Tests that the lexer can read a float.
 #[test]
 fn test_read_int() {
-    let tokens = lexer("42");
-    assert_eq!(tokens[0].value, TokenKind::Int(42));
+	let tokens = lexer("42");
+	assert_eq!(tokens[0].value, TokenKind::Int(42, None));
 }
 
 /// Tests that the lexer can read a float.
 #[test]
 fn test_read_float() {
-    let tokens = lexer("4.2");
-    assert_eq!(tokens[0].value, TokenKind::Float(4.2));
+	let tokens = lexer("4.2");
+	assert_eq!(tokens[0].value, TokenKind::Float(4.2, None));
+}
+
+/// Tests that the lexer can read number literals with prefixes, separators, exponents and suffixes.
+#[test]
+fn test_read_number_literals() {
+	let kinds: Vec<TokenKind> = lexer(
+		"0x4628 0XfF 0b1010_1010 0o777 1_000_000 1.5e-3 2E10 1e+2 42u8 1.0f32 7f64 0xffu64 18446744073709551615u64 128i8 9223372036854775808 18446744073709551615",
+	)
+	.into_iter()
+	.map(|token| token.value)
+	.collect();
+	assert_eq!(
+		kinds,
+		vec![
+			TokenKind::Int(0x4628, None),
+			TokenKind::Int(0xff, None),
+			TokenKind::Int(0b1010_1010, None),
+			TokenKind::Int(0o777, None),
+			TokenKind::Int(1_000_000, None),
+			TokenKind::Float(1.5e-3, None),
+			TokenKind::Float(2e10, None),
+			TokenKind::Float(1e2, None),
+			TokenKind::Int(42, Some(NumberSuffix::U8)),
+			TokenKind::Float(1.0, Some(NumberSuffix::F32)),
+			TokenKind::Float(7.0, Some(NumberSuffix::F64)),
+			TokenKind::Int(0xff, Some(NumberSuffix::U64)),
+			TokenKind::Int(u64::MAX, Some(NumberSuffix::U64)),
+			// Magnitudes of the minimums `-128i8` and `-9223372036854775808`
+			TokenKind::Int(128, Some(NumberSuffix::I8)),
+			TokenKind::Int(9_223_372_036_854_775_808, None),
+			// Literals without suffix are range checked against their expected type by the type checker
+			TokenKind::Int(u64::MAX, None),
+		]
+	);
+}
+
+/// Tests that malformed number literals are reported with the position of the offending part.
+#[test]
+fn test_malformed_number_literals() {
+	let expected = [
+		("0x", "0x", 1, 2),
+		("0b102", "2", 5, 5),
+		("1e+", "e+", 2, 3),
+		("12abc", "abc", 3, 5),
+		("300u8", "300u8", 1, 5),
+		("129i8", "129i8", 1, 5),
+		("18446744073709551616", "18446744073709551616", 1, 20),
+		("1e999", "1e999", 1, 5),
+		("1e999f64", "1e999f64", 1, 8),
+		("1.5u8", "u8", 4, 5),
+		("0b1f32", "0b", 1, 2),
+	];
+	for (source_code, offending, start_column, end_column) in expected {
+		let source = Arc::new(Source::new("testfile".to_owned(), source_code.to_owned()));
+		let (_, errors) = Lexer::new(&source).tokenize();
+		match &errors[..] {
+			[Error::ParseNumberError { number, .. }] => {
+				assert_eq!(number.value, offending, "in {}", source_code);
+				assert_eq!(number.position.position().start.column, start_column, "in {}", source_code);
+				assert_eq!(number.position.position().end.column, end_column, "in {}", source_code);
+			},
+			other => panic!("Expected a single ParseNumberError for {}, found {:?}", source_code, other),
+		}
+	}
 }
 
+/// Tests that the lexer resolves all escape sequences in string literals and consumes the closing quotes.
+#[test]
+fn test_read_string_literal_escapes() {
+	let kinds: Vec<TokenKind> =
+		lexer(r#""a\tb\n\r\0\\\"\'\x41\u{1F600}\u{e9}" "second""#).into_iter().map(|token| token.value).collect();
+	assert_eq!(
+		kinds,
+		vec![
+			TokenKind::StringLiteral("a\tb\n\r\0\\\"'A\u{1F600}\u{e9}".to_owned()),
+			TokenKind::StringLiteral("second".to_owned()),
+		]
+	);
+}
+
+/// Tests that the lexer can read char literals.
+#[test]
+fn test_read_char_literal() {
+	let kinds: Vec<TokenKind> = lexer(r"'a' '\n' '\'' '\u{e9}' 'é'").into_iter().map(|token| token.value).collect();
+	assert_eq!(
+		kinds,
+		vec![
+			TokenKind::CharLiteral('a'),
+			TokenKind::CharLiteral('\n'),
+			TokenKind::CharLiteral('\''),
+			TokenKind::CharLiteral('é'),
+			TokenKind::CharLiteral('é'),
+		]
+	);
+}
+
+/// Tests that malformed string and char literals are reported with the position of the offending part.
+#[test]
+fn test_malformed_literals() {
+	let expected = [
+		(r#"x = "abc"#, "UnterminatedLiteral", r#""abc"#, 5, 8),
+		("\"ab\ncd\"", "UnterminatedLiteral", "\"ab", 1, 3),
+		(r#""a\qb""#, "InvalidEscape", r"\q", 3, 4),
+		(r#""\x4""#, "InvalidEscape", r"\x4", 2, 4),
+		(r#""\xff""#, "InvalidEscape", r"\xff", 2, 5),
+		(r#""\u{110000}""#, "InvalidEscape", r"\u{110000}", 2, 11),
+		(r#""\u1234""#, "InvalidEscape", r"\u", 2, 3),
+		("''", "InvalidCharLiteral", "''", 1, 2),
+		("'ab'", "InvalidCharLiteral", "'ab'", 1, 4),
+	];
+	for (source_code, expected_error, offending, start_column, end_column) in expected {
+		let source = Arc::new(Source::new("testfile".to_owned(), source_code.to_owned()));
+		let (_, errors) = Lexer::new(&source).tokenize();
+		assert!(
+			format!("{:?}", errors[0]).starts_with(expected_error),
+			"Unexpected error {:?} in {}",
+			errors[0],
+			source_code
+		);
+		let position = errors[0].position().unwrap();
+		assert_eq!(position.get_affected_code(), offending, "in {}", source_code);
+		assert_eq!(position.position().start.column, start_column, "in {}", source_code);
+		assert_eq!(position.position().end.column, end_column, "in {}", source_code);
+	}
+}
+
+/// Tests that tokens after multi-byte chars get byte offsets and the right affected code.
+#[test]
+fn test_non_ascii_positions() {
+	let source = Arc::new(Source::new("testfile".to_owned(), "# Größe in €\ngröße = \"ä\" + 1".to_owned()));
+	let (tokens, _) = Lexer::new(&source).tokenize();
+	let tokens: Vec<(String, usize, usize)> = tokens
+		.iter()
+		.map(|token| source.locate(token.span))
+		.map(|position| {
+			(position.get_affected_code(), position.position().start.offset, position.position().start.column)
+		})
+		.collect();
+	assert_eq!(
+		tokens,
+		vec![
+			("# Größe in €".to_owned(), 0, 1),
+			("größe".to_owned(), 17, 1),
+			("=".to_owned(), 25, 7),
+			("\"ä\"".to_owned(), 27, 9),
+			("+".to_owned(), 32, 13),
+			("1".to_owned(), 34, 15),
+		]
+	);
+}
+
+/// Tests that only newlines ending a statement are emitted as end of line.
+#[test]
+fn test_end_of_line() {
+	let kinds: Vec<TokenKind> =
+		lexer("\n\na = b\n\n(c\n)\nd +\ne # comment\n# more\nf(\n)").into_iter().map(|token| token.value).collect();
+	let identifier = |name: &str| TokenKind::Identifier(name.to_owned());
+	assert_eq!(
+		kinds,
+		vec![
+			identifier("a"),
+			TokenKind::Equal,
+			identifier("b"),
+			TokenKind::EndOfLine,
+			TokenKind::OpeningParentheses,
+			identifier("c"),
+			TokenKind::ClosingParentheses,
+			TokenKind::EndOfLine,
+			identifier("d"),
+			TokenKind::Plus,
+			identifier("e"),
+			TokenKind::Comment("comment".to_owned()),
+			TokenKind::EndOfLine,
+			TokenKind::Comment("more".to_owned()),
+			identifier("f"),
+			TokenKind::OpeningParentheses,
+			TokenKind::ClosingParentheses,
+		]
+	);
+}
+
+/// Tests that `##` lines are lexed as doc comments, which are not merged with normal comment lines.
+#[test]
+fn test_doc_comment() {
+	let kinds: Vec<TokenKind> =
+		lexer("## Adds\n##\n##   indented\n# note\n##doc").into_iter().map(|token| token.value).collect();
+	assert_eq!(
+		kinds,
+		vec![
+			TokenKind::DocComment("Adds\n\n  indented".to_owned()),
+			TokenKind::Comment("note".to_owned()),
+			TokenKind::DocComment("doc".to_owned()),
+		]
+	);
+}
+
+/// Tests that `..` after a number is lexed as range and not as decimal point.
+#[test]
+fn test_range() {
+	let kinds: Vec<TokenKind> =
+		lexer("for i in 0..n step 2 0..=10 1.5..2").into_iter().map(|token| token.value).collect();
+	assert_eq!(
+		kinds,
+		vec![
+			TokenKind::For,
+			TokenKind::Identifier("i".to_owned()),
+			TokenKind::In,
+			TokenKind::Int(0, None),
+			TokenKind::DoubleDot,
+			TokenKind::Identifier("n".to_owned()),
+			TokenKind::Step,
+			TokenKind::Int(2, None),
+			TokenKind::Int(0, None),
+			TokenKind::DoubleDotEqual,
+			TokenKind::Int(10, None),
+			TokenKind::Float(1.5, None),
+			TokenKind::DoubleDot,
+			TokenKind::Int(2, None),
+		]
+	);
+}
+
+/// Tests that array types and indexes are lexed.
+#[test]
+fn test_array() {
+	let kinds: Vec<TokenKind> = lexer("arr<int>(10) a@3").into_iter().map(|token| token.value).collect();
+	assert_eq!(
+		kinds,
+		vec![
+			TokenKind::Array,
+			TokenKind::Less,
+			TokenKind::Identifier("int".to_owned()),
+			TokenKind::Greater,
+			TokenKind::OpeningParentheses,
+			TokenKind::Int(10, None),
+			TokenKind::ClosingParentheses,
+			TokenKind::Identifier("a".to_owned()),
+			TokenKind::At,
+			TokenKind::Int(3, None),
+		]
+	);
+}
+
+/// Tests that a `.` after an index is lexed as field access and not as decimal point.
+#[test]
+fn test_index_field_access() {
+	let kinds: Vec<TokenKind> = lexer("a@0.field a@i@1.field = x").into_iter().map(|token| token.value).collect();
+	let identifier = |name: &str| TokenKind::Identifier(name.to_owned());
+	assert_eq!(
+		kinds,
+		vec![
+			identifier("a"),
+			TokenKind::At,
+			TokenKind::Int(0, None),
+			TokenKind::Dot,
+			identifier("field"),
+			identifier("a"),
+			TokenKind::At,
+			identifier("i"),
+			TokenKind::At,
+			TokenKind::Int(1, None),
+			TokenKind::Dot,
+			identifier("field"),
+			TokenKind::Equal,
+			identifier("x"),
+		]
+	);
+}
+
+/// Tests that the pointer operations are lexed as keywords.
+#[test]
+fn test_pointer_keywords() {
+	let kinds: Vec<TokenKind> = lexer("ref deref alloc del nil ptr").into_iter().map(|token| token.value).collect();
+	assert_eq!(
+		kinds,
+		vec![
+			TokenKind::Reference,
+			TokenKind::Dereference,
+			TokenKind::Alloc,
+			TokenKind::Delete,
+			TokenKind::Nil,
+			TokenKind::Pointer,
+		]
+	);
+}
 
 /// Boilerplate code for converting source code into tokens using a lexer.
 fn lexer(source_code: &str) -> Vec<Token> {
-    let source = Arc::new(Source::new("testfile".to_owned(), source_code.to_owned()));
-    let lexer = Lexer::new(source.iter());
-    lexer.collect::<Result<Vec<Token>, Error>>().unwrap()
-}
\ No newline at end of file
+	let source = Arc::new(Source::new("testfile".to_owned(), source_code.to_owned()));
+	let lexer = Lexer::new(&source);
+	lexer.collect::<Result<Vec<Token>, Error>>().unwrap()
+}
+
+/// Tests that the lexer keeps going after an error and reports every bad symbol.
+#[test]
+fn test_tokenize_recovers_from_errors() {
+	let source = Arc::new(Source::new("testfile".to_owned(), "a $ b =/x ?".to_owned()));
+	let (tokens, errors) = Lexer::new(&source).tokenize();
+
+	assert_eq!(errors.len(), 3);
+	assert!(matches!(errors[0], Error::UnknownSymbol(ref symbol) if symbol.value == '$'));
+	assert!(matches!(errors[1], Error::IllegalSymbol(Some(ref symbol)) if symbol.value == 'x'));
+	assert!(matches!(errors[2], Error::UnknownSymbol(ref symbol) if symbol.value == '?'));
+
+	let kinds: Vec<TokenKind> = tokens.into_iter().map(|token| token.value).collect();
+	assert_eq!(
+		kinds,
+		vec![
+			TokenKind::Identifier("a".to_owned()),
+			TokenKind::Error,
+			TokenKind::Identifier("b".to_owned()),
+			TokenKind::Error,
+			TokenKind::Error,
+		]
+	);
+}
+
+/// Tests that the lexer can read comparison and logical operators.
+#[test]
+fn test_read_operators() {
+	let kinds: Vec<TokenKind> = lexer("< <= > >= = == =/= and or xor not bitor bitand bitxor shl shr mod %")
+		.into_iter()
+		.map(|token| token.value)
+		.collect();
+	assert_eq!(
+		kinds,
+		vec![
+			TokenKind::Less,
+			TokenKind::LessEqual,
+			TokenKind::Greater,
+			TokenKind::GreaterEqual,
+			TokenKind::Equal,
+			TokenKind::DoubleEqual,
+			TokenKind::NotEqual,
+			TokenKind::And,
+			TokenKind::Or,
+			TokenKind::Xor,
+			TokenKind::Not,
+			TokenKind::BitOr,
+			TokenKind::BitAnd,
+			TokenKind::BitXor,
+			TokenKind::ShiftLeft,
+			TokenKind::ShiftRight,
+			TokenKind::Modulus,
+			TokenKind::Modulus,
+		]
+	);
+}
+
+/// Tests that block comments can be nested and span multiple lines.
+#[test]
+fn test_block_comment() {
+	let kinds: Vec<TokenKind> =
+		lexer("a #[ outer #[ inner ]#\n still ]# b\n#[]#\n# line").into_iter().map(|token| token.value).collect();
+	assert_eq!(
+		kinds,
+		vec![
+			TokenKind::Identifier("a".to_owned()),
+			TokenKind::Comment("#[ outer #[ inner ]#\n still ]#".to_owned()),
+			TokenKind::Identifier("b".to_owned()),
+			TokenKind::EndOfLine,
+			TokenKind::Comment("#[]#".to_owned()),
+			TokenKind::Comment("line".to_owned()),
+		]
+	);
+}
+
+/// Tests that an unterminated block comment is reported at its opener.
+#[test]
+fn test_unterminated_block_comment() {
+	let source = Arc::new(Source::new("testfile".to_owned(), "a\n  #[ #[ ]#\nb".to_owned()));
+	let (tokens, errors) = Lexer::new(&source).tokenize();
+	assert_eq!(errors.len(), 1);
+	let Error::UnterminatedBlockComment(ref opener) = errors[0] else { panic!("Unexpected error {:?}", errors[0]) };
+	assert_eq!(opener.value, "#[");
+	assert_eq!(opener.position.position().start.line, 2);
+	assert_eq!(opener.position.position().start.column, 3);
+	assert_eq!(opener.position.position().end.column, 4);
+	assert_eq!(tokens.last().map(|token| &token.value), Some(&TokenKind::Error));
+}
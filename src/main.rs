@@ -6,7 +6,7 @@ use anyhow::Context;
 use fortytwolang::{
 	emitter::{self, Emitter},
 	lexer::{self},
-	parser::{self, Error},
+	parser,
 	semantic_analyzer::{self},
 	source::SourcePositionRange,
 };
@@ -28,9 +28,10 @@ fn main() {
 	let args = <cli::Args as clap::Parser>::parse();
 
 	let result = match args.command {
-		cli::Command::Compile { file: path } => compile(&path),
-		cli::Command::Run { file: path } => run(&path),
+		cli::Command::Compile { file: path, debug } => compile(&path, debug),
+		cli::Command::Run { file: path, debug } => run(&path, debug),
 		cli::Command::Fmt { file: path } => format(&path),
+		cli::Command::Doc { file: path, format } => document(&path, format),
 	};
 
 	if let Err(err) = result {
@@ -48,8 +49,19 @@ fn format(path: &Path) -> anyhow::Result<()> {
 	Ok(())
 }
 
-/// Compiles FTL source code to a C executable.
-fn compile(path: &Path) -> anyhow::Result<()> {
+/// Generates the API documentation of FTL source code.
+fn document(path: &Path, format: cli::DocFormat) -> anyhow::Result<()> {
+	let ast_nodes = fortytwolang::compiler_pipeline(path)?;
+
+	match format {
+		cli::DocFormat::Markdown => emitter::Markdown::codegen(ast_nodes.into_iter(), Box::new(io::stdout()))?,
+		cli::DocFormat::Html => emitter::Html::codegen(ast_nodes.into_iter(), Box::new(io::stdout()))?,
+	}
+	Ok(())
+}
+
+/// Compiles FTL source code to a C executable. In `debug` mode, array indexes are checked at runtime.
+fn compile(path: &Path, debug: bool) -> anyhow::Result<()> {
 	let ast_nodes = fortytwolang::compiler_pipeline(path)?;
 
 	// Compile to c code
@@ -61,10 +73,16 @@ fn compile(path: &Path) -> anyhow::Result<()> {
 
 	// Compile to executable
 	let executable_output_path = Path::new(&path).with_extension("");
-	let c_compile = process::Command::new("cc")
-		.args([c_code_output_path.to_string_lossy().as_ref(), "-o", executable_output_path.to_string_lossy().as_ref()])
-		.output()
-		.context("Invoking C compiler")?;
+	let mut c_compile = process::Command::new("cc");
+	c_compile.args([
+		c_code_output_path.to_string_lossy().as_ref(),
+		"-o",
+		executable_output_path.to_string_lossy().as_ref(),
+	]);
+	if debug {
+		c_compile.arg("-DFTL_DEBUG");
+	}
+	let c_compile = c_compile.output().context("Invoking C compiler")?;
 	if !c_compile.status.success() {
 		io::stdout().write_all(&c_compile.stdout).unwrap();
 		io::stderr().write_all(&c_compile.stderr).unwrap();
@@ -74,8 +92,8 @@ fn compile(path: &Path) -> anyhow::Result<()> {
 }
 
 /// Compiles and runs the executable.
-fn run(path: &Path) -> anyhow::Result<()> {
-	compile(path)?;
+fn run(path: &Path, debug: bool) -> anyhow::Result<()> {
+	compile(path, debug)?;
 
 	let executable = format!("./{}", Path::new(&path).with_extension("").to_string_lossy());
 	let executing_err = process::Command::new(&executable)
@@ -90,40 +108,19 @@ fn run(path: &Path) -> anyhow::Result<()> {
 fn print_error(err: anyhow::Error) {
 	let mut message = String::new();
 
-	if let Some(err) = err.downcast_ref::<lexer::Error>() {
-		message += "LexerError\n";
-		match err {
-			lexer::Error::UnknownSymbol(symbol) => {
-				message += &format!("{}\n{}", err, highlight_position_range(&symbol.position));
-			},
-			lexer::Error::IllegalSymbol(symbol) => {
-				message += &format!(
-					"{}\n{}",
-					err,
-					symbol.as_ref().map(|s| highlight_position_range(&s.position)).unwrap_or_default()
-				);
-			},
-			lexer::Error::ParseNumberError(number_str) => {
-				message += &format!("{}\n{}", err, highlight_position_range(&number_str.position));
-			},
+	if let Some(errors) = err.downcast_ref::<lexer::Errors>() {
+		for (i, err) in errors.0.iter().enumerate() {
+			if i != 0 {
+				message += "\n\n";
+			}
+			message += &lexer_error_message(err);
 		}
-	} else if let Some(err) = err.downcast_ref::<parser::Error>() {
-		message += "ParserError\n";
-		match err {
-			Error::ExpectedToken { found, .. } => {
-				message += &format!(
-					"{}\n{}",
-					err,
-					found.as_ref().map(|found| { highlight_position_range(&found.position) }).unwrap_or_default()
-				);
-			},
-			Error::IllegalToken { token, .. } => {
-				message += &format!(
-					"{}\n{}",
-					err,
-					token.as_ref().map(|found| { highlight_position_range(&found.position) }).unwrap_or_default()
-				);
-			},
+	} else if let Some(errors) = err.downcast_ref::<parser::Errors>() {
+		for (i, err) in errors.0.iter().enumerate() {
+			if i != 0 {
+				message += "\n\n";
+			}
+			message += &parser_error_message(err);
 		}
 	} else if let Some(err) = err.downcast_ref::<semantic_analyzer::Error>() {
 		message += "SemanticError\n";
@@ -137,6 +134,51 @@ fn print_error(err: anyhow::Error) {
 			semantic_analyzer::Error::TypeMismatch { position, .. } => {
 				message += &format!("{}\n{}", err, highlight_position_range(position))
 			},
+			semantic_analyzer::Error::IllegalOperandType { position, .. } => {
+				message += &format!("{}\n{}", err, highlight_position_range(position))
+			},
+			semantic_analyzer::Error::NotAddressable { position } => {
+				message += &format!("{}\n{}", err, highlight_position_range(position))
+			},
+			semantic_analyzer::Error::NilNotPointer { position, .. } => {
+				message += &format!("{}\n{}", err, highlight_position_range(position))
+			},
+			semantic_analyzer::Error::UntypedNil { position } => {
+				message += &format!("{}\n{}", err, highlight_position_range(position))
+			},
+			semantic_analyzer::Error::NotInterpolatable { position, .. } => {
+				message += &format!("{}\n{}", err, highlight_position_range(position))
+			},
+			semantic_analyzer::Error::LiteralOutOfRange { position, .. } => {
+				message += &format!("{}\n{}", err, highlight_position_range(position))
+			},
+			semantic_analyzer::Error::NoValues { position, .. } => {
+				message += &format!("{}\n{}", err, highlight_position_range(position))
+			},
+			semantic_analyzer::Error::NotIterable { position, .. } => {
+				message += &format!("{}\n{}", err, highlight_position_range(position))
+			},
+			semantic_analyzer::Error::JumpOutsideLoop { position, .. } => {
+				message += &format!("{}\n{}", err, highlight_position_range(position))
+			},
+			semantic_analyzer::Error::UnknownLabel { label } => {
+				message += &format!("{}\n{}", err, highlight_position_range(&label.position))
+			},
+			semantic_analyzer::Error::UnknownField { field, .. } => {
+				message += &format!("{}\n{}", err, highlight_position_range(&field.position))
+			},
+			semantic_analyzer::Error::MissingField { position, .. } => {
+				message += &format!("{}\n{}", err, highlight_position_range(position))
+			},
+			semantic_analyzer::Error::DuplicateField { field } => {
+				message += &format!("{}\n{}", err, highlight_position_range(&field.position))
+			},
+			semantic_analyzer::Error::IndexOutOfBounds { position, .. } => {
+				message += &format!("{}\n{}", err, highlight_position_range(position))
+			},
+			semantic_analyzer::Error::UndefinedStruct { position, .. } => {
+				message += &format!("{}\n{}", err, highlight_position_range(position))
+			},
 			semantic_analyzer::Error::UndefinedFunctionCall { function_call } => {
 				message += &format!("{}\n{}", err, highlight_position_range(&function_call.name.position))
 			},
@@ -152,24 +194,20 @@ fn print_error(err: anyhow::Error) {
 	eprintln!("{}", message);
 }
 
-/// Highlights/underlines the affected position range in the source code line.
-fn highlight_position_range(position: &SourcePositionRange) -> String {
-	let affected_code = position.get_affected_lines();
-
-	let mut output = String::new();
-
-	for line_with_whitespaces in affected_code.lines() {
-		let line = line_with_whitespaces.trim_start();
-		let spaces_removed = line_with_whitespaces.len() - line.len();
+/// Formats a single lexer error with the affected source code highlighted.
+fn lexer_error_message(err: &lexer::Error) -> String {
+	let highlight = err.position().map(highlight_position_range).unwrap_or_default();
+	format!("LexerError\n{}\n{}", err, highlight)
+}
 
-		// Write source code line
-		output.push_str(line);
-		output.push('\n');
+/// Formats a single parser error with the affected source code highlighted.
+fn parser_error_message(err: &parser::Error) -> String {
+	let highlight = err.position().map(highlight_position_range).unwrap_or_default();
+	format!("ParserError\n{}\n{}", err, highlight)
+}
 
-		// Write underline
-		output.push_str(&" ".repeat(position.position.start.column - 1 - spaces_removed));
-		let highlight_width = position.position.end.column - position.position.start.column + 1;
-		output.push_str(&"^".repeat(highlight_width));
-	}
-	output
+/// Highlights/underlines the affected position range in the source code lines.
+fn highlight_position_range(position: &SourcePositionRange) -> String {
+	// Lines and columns are only resolved here, when the error is rendered
+	position.highlight()
 }
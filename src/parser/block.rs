@@ -1,22 +1,102 @@
-use std::iter::Peekable;
+use std::mem;
 
-use super::Result;
+use super::{Error, Result, TokenStream};
 use crate::{
-	ast::Instruction,
+	ast::{Block, Commented, Comments},
 	parser::{helper, instruction::parse_instruction},
 	token::{Token, TokenKind},
 };
 
-pub fn parse_block(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Result<Vec<Instruction>> {
-	let mut block: Vec<Instruction> = Vec::new();
-	helper::parse_opening_curly_parenthesis(tokens.next())?;
-	while let Some(token) = tokens.peek() {
-		if let TokenKind::ClosingCurlyBraces = **token {
-			tokens.next(); // Consume TokenKind::ClosingParentheses
-			break; // End of block
+/// Parses a block enclosed by curly braces.
+///
+/// Each instruction must be ended by a newline or `;`, or be the last instruction in the block. Empty lines and
+/// empty statements are skipped. Comments are attached to the instruction before (on the same line) or after them,
+/// and comments after the last instruction are stored in the block.
+///
+/// Errors in instructions are collected in `errors` and parsing resumes at the next instruction boundary (see
+/// [`synchronize_instruction`]), so the returned block contains all instructions that could be parsed.
+pub fn parse_block(tokens: &mut TokenStream<impl Iterator<Item = Token>>, errors: &mut Vec<Error>) -> Result<Block> {
+	let mut block = Block::default();
+	// Comments inside the expressions before the block, e.g. in the condition of an `if`, lead its first instruction
+	let mut leading_comments = tokens.take_comments();
+	helper::parse_opening_curly_parenthesis(tokens)?;
+	loop {
+		match tokens.peek().map(|token| &token.value) {
+			Some(TokenKind::ClosingCurlyBraces) => {
+				tokens.next(); // Consume TokenKind::ClosingParentheses
+				break; // End of block
+			},
+			// Missing closing brace, the block ended with the file or a new top level node begins
+			None | Some(TokenKind::Def | TokenKind::Extern | TokenKind::Struct) => {
+				errors.push(Error::ExpectedToken {
+					expected: TokenKind::ClosingCurlyBraces,
+					found: tokens.peek_located(),
+				});
+				break;
+			},
+			// Empty line or empty statement
+			Some(TokenKind::EndOfLine | TokenKind::Semicolon) => {
+				tokens.next();
+			},
+			Some(TokenKind::Comment(_) | TokenKind::DocComment(_)) => {
+				leading_comments.extend(helper::parse_comments(tokens))
+			},
+			Some(_) => match parse_instruction(tokens, errors) {
+				Ok(instruction) => {
+					// Comments inside the instruction that were not attached to any of its nodes and comments before
+					// the end of the line are trailing comments of the instruction
+					let mut trailing = tokens.take_comments();
+					trailing.extend(helper::parse_comments(tokens));
+					let comments =
+						Comments { leading: mem::take(&mut leading_comments), trailing, following: Vec::new() };
+					block.instructions.push(Commented::new(instruction, comments));
+					if let Err(err) = helper::parse_statement_end(tokens) {
+						errors.push(err);
+						synchronize_instruction(tokens);
+					}
+				},
+				Err(err) => {
+					errors.push(err);
+					tokens.take_comments();
+					synchronize_instruction(tokens);
+				},
+			},
 		}
-		let instruction = parse_instruction(tokens)?;
-		block.push(instruction);
 	}
+	block.comments = leading_comments;
 	Ok(block)
 }
+
+/// Skips tokens until the start of the next instruction, the end of the enclosing block or the start of a new top
+/// level node. A newline or `;` ending the erroneous instruction is consumed.
+///
+/// Nested blocks of the erroneous instruction are skipped as a whole.
+fn synchronize_instruction(tokens: &mut TokenStream<impl Iterator<Item = Token>>) {
+	let mut depth: usize = 0;
+	while let Some(token) = tokens.peek() {
+		match token.value {
+			TokenKind::Def | TokenKind::Extern | TokenKind::Struct => return,
+			TokenKind::ClosingCurlyBraces if depth == 0 => return,
+			TokenKind::EndOfLine | TokenKind::Semicolon if depth == 0 => {
+				tokens.next(); // Consume the end of the erroneous instruction
+				return;
+			},
+			TokenKind::Var
+			| TokenKind::If
+			| TokenKind::While
+			| TokenKind::For
+			| TokenKind::Break
+			| TokenKind::Continue
+			| TokenKind::Delete
+			| TokenKind::Return
+				if depth == 0 =>
+			{
+				return
+			},
+			TokenKind::OpeningCurlyBraces => depth += 1,
+			TokenKind::ClosingCurlyBraces => depth -= 1,
+			_ => (),
+		}
+		tokens.next();
+	}
+}
@@ -2,13 +2,36 @@ use core::fmt;
 
 use thiserror::Error;
 
-use crate::token::{Token, TokenKind};
+use crate::{
+	source::{PositionContainer, SourcePositionRange},
+	token::{LocatedToken, TokenKind},
+};
 
 #[derive(Debug, Clone, PartialEq, Error)]
 pub enum Error {
-	ExpectedToken { expected: TokenKind, found: Option<Token> },
+	ExpectedToken {
+		expected: TokenKind,
+		found: Option<LocatedToken>,
+	},
 
-	IllegalToken { token: Option<Token>, context: &'static str },
+	IllegalToken {
+		token: Option<LocatedToken>,
+		context: &'static str,
+	},
+
+	/// A [reserved keyword](crate::lexer::RESERVED_KEYWORDS) used as name of a variable, function, struct or field.
+	ReservedKeyword(PositionContainer<String>),
+}
+
+impl Error {
+	/// Returns the position of the offending token, if there is one.
+	pub fn position(&self) -> Option<&SourcePositionRange> {
+		match self {
+			Error::ExpectedToken { found, .. } => found.as_ref().map(|token| &token.position),
+			Error::IllegalToken { token, .. } => token.as_ref().map(|token| &token.position),
+			Error::ReservedKeyword(name) => Some(&name.position),
+		}
+	}
 }
 
 impl fmt::Display for Error {
@@ -22,6 +45,25 @@ impl fmt::Display for Error {
 				Some(token) => write!(f, "{} Illegal token '{:?}' in {}", token.position, token.value, context),
 				None => write!(f, "Illegal token in {}", context),
 			},
+			Error::ReservedKeyword(name) => {
+				write!(f, "{} `{}` is a reserved keyword and cannot be used as name", name.position, name.value)
+			},
+		}
+	}
+}
+
+/// All [`Error`]s found while [parsing](crate::parser::Parser::parse) a source file.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub struct Errors(pub Vec<Error>);
+
+impl fmt::Display for Errors {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for (i, error) in self.0.iter().enumerate() {
+			if i != 0 {
+				writeln!(f)?;
+			}
+			write!(f, "{}", error)?;
 		}
+		Ok(())
 	}
 }
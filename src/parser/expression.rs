@@ -1,50 +1,222 @@
-use std::iter::Peekable;
+use std::ops::Range;
 
-use super::Result;
+use super::{Result, TokenStream};
 use crate::{
 	ast,
-	ast::{
-		expression::{BinaryOperator, NumberKind},
-		Expression,
-	},
-	parser::{function::parse_function_call, helper, helper::parse_operator, Error},
-	source::PositionContainer,
+	ast::expression::{NumberKind, UnaryOperator},
+	parser::{function::parse_function_call, helper, variable, Error},
+	source::{PositionContainer, Span},
 	token::{Token, TokenKind},
 };
 
-pub(crate) fn parse_primary_expression(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Result<ast::Expression> {
+pub(crate) fn parse_primary_expression(
+	tokens: &mut TokenStream<impl Iterator<Item = Token>>,
+) -> Result<ast::Expression> {
+	// Comments in front of an expression, e.g. after the `=` of an assignment at the end of a line, have no node to
+	// be attached to, so they are kept for the enclosing instruction
+	let comments = helper::parse_comments(tokens);
+	tokens.keep_comments(comments);
+	if let Some(Token {
+		value: TokenKind::Minus | TokenKind::Not | TokenKind::BitNot | TokenKind::Reference | TokenKind::Dereference,
+		..
+	}) = tokens.peek()
+	{
+		// The operand of the unary expression already contains its field accesses and indexes
+		return parse_unary_expression(tokens);
+	}
+	let operand = parse_operand(tokens)?;
+	parse_postfix_expressions(operand, tokens)
+}
+
+/// Parses a variable, function call, number, string, struct literal, allocation, `nil` or parenthesized expression.
+fn parse_operand(tokens: &mut TokenStream<impl Iterator<Item = Token>>) -> Result<ast::Expression> {
 	match tokens.peek() {
-		Some(Token { value: TokenKind::Identifier(_), .. }) => Ok(parse_identifier_expression(tokens)?),
-		Some(Token { value: TokenKind::Float(_), .. }) => Ok(ast::Expression::Number(parse_float(tokens)?)),
-		Some(Token { value: TokenKind::Int(_), .. }) => Ok(ast::Expression::Number(parse_int(tokens)?)),
-		Some(Token { value: TokenKind::OpeningParentheses, .. }) => Ok(parse_parentheses(tokens)?),
-		other => Err(Error::IllegalToken { token: other.cloned(), context: "expression" }),
+		Some(Token { value: TokenKind::Identifier(_), .. }) => parse_identifier_expression(tokens),
+		Some(Token { value: TokenKind::Float(..), .. }) => Ok(ast::Expression::Number(parse_float(tokens)?)),
+		Some(Token { value: TokenKind::Int(..), .. }) => Ok(ast::Expression::Number(parse_int(tokens)?)),
+		Some(Token { value: TokenKind::StringLiteral(_), .. }) => {
+			Ok(ast::Expression::String(parse_string_literal(tokens)?))
+		},
+		Some(Token { value: TokenKind::OpeningParentheses, .. }) => parse_parentheses(tokens),
+		Some(Token { value: TokenKind::New, .. }) => Ok(ast::Expression::StructLiteral(parse_struct_literal(tokens)?)),
+		Some(Token { value: TokenKind::Alloc, .. }) => Ok(ast::Expression::Alloc(parse_alloc(tokens)?)),
+		Some(Token { value: TokenKind::Nil, .. }) => {
+			let span = tokens.next().unwrap().span;
+			Ok(ast::Expression::Nil(tokens.position_of(span)))
+		},
+		_ => Err(Error::IllegalToken { token: tokens.peek_located(), context: "expression" }),
 	}
 }
 
-pub fn parse_float(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Result<PositionContainer<NumberKind>> {
+/// Parses the field accesses like `.b` and indexes like `@3` following `base`, which bind stronger than any
+/// operator.
+///
+/// The index is an operand without field accesses and indexes, so `a@i.b` accesses the field `b` of the element
+/// `a@i`. Indexes containing operators need to be enclosed in parentheses like in `a@(i + 1)`.
+pub(crate) fn parse_postfix_expressions(
+	mut base: ast::Expression,
+	tokens: &mut TokenStream<impl Iterator<Item = Token>>,
+) -> Result<ast::Expression> {
+	loop {
+		base = match tokens.peek() {
+			Some(Token { value: TokenKind::Dot, .. }) => {
+				tokens.next(); // Consume the TokenKind::Dot
+				let field = helper::parse_identifier(tokens)?;
+				ast::Expression::FieldAccess(ast::expression::FieldAccess { base: Box::new(base), field })
+			},
+			Some(Token { value: TokenKind::At, .. }) => {
+				tokens.next(); // Consume the TokenKind::At
+				let index = parse_operand(tokens)?;
+				ast::Expression::Index(ast::expression::Index { base: Box::new(base), index: Box::new(index) })
+			},
+			_ => return Ok(base),
+		};
+	}
+}
+
+/// Parses a unary operator followed by its operand.
+///
+/// The operand is a primary expression, so unary operators bind stronger than binary operators.
+pub fn parse_unary_expression(tokens: &mut TokenStream<impl Iterator<Item = Token>>) -> Result<ast::Expression> {
+	let operator = match tokens.next() {
+		Some(Token { value: TokenKind::Minus, span }) => {
+			PositionContainer::new(UnaryOperator::Negate, tokens.position_of(span))
+		},
+		Some(Token { value: TokenKind::Not, span }) => {
+			PositionContainer::new(UnaryOperator::Not, tokens.position_of(span))
+		},
+		Some(Token { value: TokenKind::BitNot, span }) => {
+			PositionContainer::new(UnaryOperator::Complement, tokens.position_of(span))
+		},
+		Some(Token { value: TokenKind::Reference, span }) => {
+			PositionContainer::new(UnaryOperator::Reference, tokens.position_of(span))
+		},
+		Some(Token { value: TokenKind::Dereference, span }) => {
+			PositionContainer::new(UnaryOperator::Dereference, tokens.position_of(span))
+		},
+		other => return Err(Error::IllegalToken { token: tokens.locate(other), context: "unary expression" }),
+	};
+	let comments = helper::parse_comments(tokens);
+	let operand = parse_primary_expression(tokens)?;
+	Ok(ast::Expression::Unary(ast::expression::UnaryExpression { operator, comments, operand: Box::new(operand) }))
+}
+
+pub fn parse_float(tokens: &mut TokenStream<impl Iterator<Item = Token>>) -> Result<PositionContainer<NumberKind>> {
 	match tokens.next() {
-		Some(Token { value: TokenKind::Float(float), position }) => {
-			Ok(PositionContainer::new(NumberKind::Float(float), position))
+		Some(Token { value: TokenKind::Float(float, suffix), span }) => {
+			Ok(PositionContainer::new(NumberKind::Float(float, suffix), tokens.position_of(span)))
 		},
-		Some(Token { value: TokenKind::Int(int), position }) => {
-			Ok(PositionContainer::new(NumberKind::Int(int), position))
+		Some(Token { value: TokenKind::Int(int, suffix), span }) => {
+			Ok(PositionContainer::new(NumberKind::Int(int, suffix), tokens.position_of(span)))
 		},
-		other => Err(Error::ExpectedToken { expected: TokenKind::Float(0.0), found: other }),
+		other => Err(Error::ExpectedToken { expected: TokenKind::Float(0.0, None), found: tokens.locate(other) }),
 	}
 }
 
-pub fn parse_int(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Result<PositionContainer<NumberKind>> {
+pub fn parse_int(tokens: &mut TokenStream<impl Iterator<Item = Token>>) -> Result<PositionContainer<NumberKind>> {
 	match tokens.next() {
-		Some(Token { value: TokenKind::Int(int), position }) => {
-			Ok(PositionContainer::new(NumberKind::Int(int), position))
+		Some(Token { value: TokenKind::Int(int, suffix), span }) => {
+			Ok(PositionContainer::new(NumberKind::Int(int, suffix), tokens.position_of(span)))
 		},
-		other => Err(Error::ExpectedToken { expected: TokenKind::Int(0), found: other }),
+		other => Err(Error::ExpectedToken { expected: TokenKind::Int(0, None), found: tokens.locate(other) }),
 	}
 }
 
-pub fn parse_identifier_expression(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Result<ast::Expression> {
-	let identifier = helper::parse_identifier(tokens.next())?;
+/// Parses a string literal like `"{name} is {person.age}"` into its texts and interpolated names or field accesses.
+/// Braces that are not part of an interpolation are written as `{{` and `}}`.
+pub fn parse_string_literal(
+	tokens: &mut TokenStream<impl Iterator<Item = Token>>,
+) -> Result<ast::expression::StringLiteral> {
+	let token = tokens.next();
+	let Some(Token { value: TokenKind::StringLiteral(content), span }) = &token else {
+		return Err(Error::ExpectedToken {
+			expected: TokenKind::StringLiteral(String::new()),
+			found: tokens.locate(token),
+		});
+	};
+	let position = tokens.position_of(*span);
+	let illegal_interpolation =
+		|| Error::IllegalToken { token: tokens.locate(token.clone()), context: "string interpolation" };
+
+	// Each char of the content is paired with its offset in the source code, to locate the interpolated names
+	let Range { start: literal_start, end: literal_end } = span.range();
+	let offsets = char_offsets(&position.source.text[literal_start + 1..literal_end - 1]);
+	let mut chars = content.chars().zip(offsets.into_iter().map(|offset| literal_start + 1 + offset)).peekable();
+	let mut parts = Vec::new();
+	let mut text = String::new();
+	while let Some((char_, _)) = chars.next() {
+		match char_ {
+			'{' if chars.next_if(|(next, _)| *next == '{').is_some() => text.push('{'),
+			'}' if chars.next_if(|(next, _)| *next == '}').is_some() => text.push('}'),
+			'{' => {
+				// A name, possibly followed by field accesses like `.name`
+				let mut value: Option<ast::Expression> = None;
+				loop {
+					let mut name = String::new();
+					let start = chars.peek().map_or(literal_end, |(_, offset)| *offset);
+					let (end, terminator) = loop {
+						match chars.next() {
+							Some((terminator @ ('}' | '.'), offset)) if !name.is_empty() => break (offset, terminator),
+							Some((char_, _)) if char_.is_alphanumeric() || char_ == '_' => name.push(char_),
+							_ => return Err(illegal_interpolation()),
+						}
+					};
+					if !name.starts_with(char::is_alphabetic) {
+						return Err(illegal_interpolation());
+					}
+					let name = PositionContainer::new(name, tokens.position_of(Span::new(start, end)));
+					value = Some(match value {
+						None => ast::Expression::Variable(name),
+						Some(base) => ast::Expression::FieldAccess(ast::expression::FieldAccess {
+							base: Box::new(base),
+							field: name,
+						}),
+					});
+					if terminator == '}' {
+						break;
+					}
+				}
+				if !text.is_empty() {
+					parts.push(ast::expression::StringPart::Text(std::mem::take(&mut text)));
+				}
+				parts.push(ast::expression::StringPart::Interpolation(value.expect("Interpolations contain a name")));
+			},
+			'}' => return Err(illegal_interpolation()),
+			_ => text.push(char_),
+		}
+	}
+	if !text.is_empty() {
+		parts.push(ast::expression::StringPart::Text(text));
+	}
+	Ok(ast::expression::StringLiteral { position, parts })
+}
+
+/// Returns the byte offsets of the chars of a string literal's content in its source code `raw` between the quotes.
+///
+/// Each escape sequence like `\n` or `\u{1F600}` is a single char of the content. The literal was lexed without
+/// errors, so all escape sequences are valid.
+fn char_offsets(raw: &str) -> Vec<usize> {
+	let mut offsets = Vec::new();
+	let mut chars = raw.char_indices();
+	while let Some((offset, char_)) = chars.next() {
+		offsets.push(offset);
+		if char_ == '\\' {
+			match chars.next() {
+				Some((_, 'x')) => {
+					chars.nth(1);
+				},
+				Some((_, 'u')) => {
+					chars.find(|(_, char_)| *char_ == '}');
+				},
+				_ => (),
+			}
+		}
+	}
+	offsets
+}
+
+pub fn parse_identifier_expression(tokens: &mut TokenStream<impl Iterator<Item = Token>>) -> Result<ast::Expression> {
+	let identifier = helper::parse_identifier(tokens)?;
 	match tokens.peek() {
 		Some(Token { value: TokenKind::OpeningParentheses, .. }) => {
 			Ok(ast::Expression::FunctionCall(parse_function_call(tokens, identifier)?))
@@ -53,54 +225,94 @@ pub fn parse_identifier_expression(tokens: &mut Peekable<impl Iterator<Item = To
 	}
 }
 
-pub fn parse_parentheses(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Result<ast::expression::Expression> {
-	helper::parse_opening_parenthesis(tokens.next())?;
+/// Parses a struct literal like `new Person(name="Linus", age=19)`.
+pub fn parse_struct_literal(
+	tokens: &mut TokenStream<impl Iterator<Item = Token>>,
+) -> Result<ast::expression::StructLiteral> {
+	helper::parse_new(tokens)?;
+	let name = helper::parse_identifier(tokens)?;
+	let (fields, comments) = helper::parse_parenthesized_list(tokens, parse_field_initializer)?;
+	Ok(ast::expression::StructLiteral { name, fields, comments })
+}
+
+/// Parses an allocation like `alloc Person`.
+fn parse_alloc(tokens: &mut TokenStream<impl Iterator<Item = Token>>) -> Result<ast::expression::Alloc> {
+	let position = helper::parse_alloc(tokens)?;
+	let data_type = variable::parse_data_type(tokens)?;
+	Ok(ast::expression::Alloc { position, data_type })
+}
+
+/// Parses the initial value of a field like `age=19`.
+fn parse_field_initializer(
+	tokens: &mut TokenStream<impl Iterator<Item = Token>>,
+) -> Result<ast::expression::FieldInitializer> {
+	let name = helper::parse_identifier(tokens)?;
+	helper::parse_equal(tokens)?;
+	let value = parse_binary_expression(tokens)?;
+	Ok(ast::expression::FieldInitializer { name, value })
+}
+
+pub fn parse_parentheses(tokens: &mut TokenStream<impl Iterator<Item = Token>>) -> Result<ast::expression::Expression> {
+	helper::parse_opening_parenthesis(tokens)?;
 	let expression = parse_binary_expression(tokens)?;
-	helper::parse_closing_parenthesis(tokens.next())?;
+	helper::parse_closing_parenthesis(tokens)?;
 	Ok(expression)
 }
 
+/// Parses an expression consisting of primary expressions connected by binary operators.
 pub(crate) fn parse_binary_expression(
-	tokens: &mut Peekable<impl Iterator<Item = Token>>,
+	tokens: &mut TokenStream<impl Iterator<Item = Token>>,
+) -> Result<ast::expression::Expression> {
+	parse_binary_expression_with_binding_power(tokens, 0)
+}
+
+/// Pratt parser for binary expressions.
+///
+/// Parses operands and operators as long as the next operator has a left [binding power](ast::expression::BinaryOperator::binding_power)
+/// of at least `min_binding_power`. The rhs of each operator is parsed recursively with the right binding power of
+/// that operator as minimum, so that stronger binding operators end up deeper in the tree.
+fn parse_binary_expression_with_binding_power(
+	tokens: &mut TokenStream<impl Iterator<Item = Token>>,
+	min_binding_power: u8,
 ) -> Result<ast::expression::Expression> {
 	let lhs = parse_primary_expression(tokens)?;
-	parse_binary_expression_rhs(lhs, None, tokens)
+	parse_binary_expression_rhs(lhs, min_binding_power, tokens)
 }
 
-fn parse_binary_expression_rhs(
-	lhs: Expression,
-	min_operator: Option<&BinaryOperator>,
-	tokens: &mut Peekable<impl Iterator<Item = Token>>,
+/// Continues parsing a binary expression after its first operand `lhs` was already parsed.
+///
+/// See [`parse_binary_expression_with_binding_power`] for the meaning of `min_binding_power`.
+pub(crate) fn parse_binary_expression_rhs(
+	mut lhs: ast::Expression,
+	min_binding_power: u8,
+	tokens: &mut TokenStream<impl Iterator<Item = Token>>,
 ) -> Result<ast::expression::Expression> {
-	let mut lhs: ast::Expression = lhs;
 	loop {
 		// Read the operator after lhs and before rhs
-		let operator = match parse_operator(tokens.peek().cloned()) {
-			// Found an operator
-			Ok(operator) => operator,
+		let Some((operator, span)) =
+			tokens.peek().and_then(|token| Some((helper::binary_operator(&token.value)?, token.span)))
+		else {
 			// No operator found
-			Err(_) => return Ok(lhs),
+			return Ok(lhs);
 		};
-		// Consume operator
-		tokens.next();
-
-		// Parse the primary expression after the operator as rhs
-		let mut rhs = parse_primary_expression(tokens)?;
+		let operator = PositionContainer::new(operator, tokens.position_of(span));
 
-		// Inspect the next operator after rhs. If it has a higher precedence than the current operator,
-		// let rhs be the result of a recursive call to parse_binary_expression_rhs with rhs as lhs.
-		if let Ok(next_operator) = parse_operator(tokens.peek().cloned()) {
-			if next_operator > operator {
-				rhs = parse_binary_expression_rhs(rhs, Some(&next_operator), tokens)?;
-			}
+		// The operator binds weaker than the operator left of lhs, so lhs belongs to the left operator
+		let (left_binding_power, right_binding_power) = operator.binding_power();
+		if left_binding_power < min_binding_power {
+			return Ok(lhs);
 		}
+		tokens.next(); // Consume operator
+		let comments = helper::parse_comments(tokens);
+
+		let rhs = parse_binary_expression_with_binding_power(tokens, right_binding_power)?;
 
 		// Merge lhs and rhs into a new lhs
 		lhs = ast::Expression::BinaryExpression(ast::expression::BinaryExpression {
 			lhs: Box::new(lhs),
 			rhs: Box::new(rhs),
 			operator,
+			comments,
 		});
 	}
-	Ok(lhs)
 }
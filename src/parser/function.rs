@@ -1,70 +1,54 @@
-use std::iter::Peekable;
-
-use super::Result;
+use super::{Result, TokenStream};
 use crate::{
 	ast,
-	ast::Expression,
-	parser::{block::parse_block, expression::parse_primary_expression, helper, variable},
+	ast::{Comment, Commented, Expression},
+	parser::{block::parse_block, expression::parse_binary_expression, helper, variable, Error},
 	source::PositionContainer,
 	token::{Token, TokenKind},
 };
 
 pub fn parse_function_definition(
-	tokens: &mut Peekable<impl Iterator<Item = Token>>,
+	tokens: &mut TokenStream<impl Iterator<Item = Token>>,
+	doc: Option<Comment>,
+	errors: &mut Vec<Error>,
 ) -> Result<ast::statement::FunctionDefinition> {
 	tokens.next(); // Consume TokenKind::FunctionDefinition
-	let prototype = parse_function_prototype(tokens)?;
-	let body = parse_block(tokens)?;
+	let prototype = parse_function_prototype(tokens, doc)?;
+	let body = parse_block(tokens, errors)?;
 	Ok(ast::statement::FunctionDefinition { prototype, body })
 }
 
 pub fn parse_extern_function_declaration(
-	tokens: &mut Peekable<impl Iterator<Item = Token>>,
+	tokens: &mut TokenStream<impl Iterator<Item = Token>>,
+	doc: Option<Comment>,
 ) -> Result<ast::statement::FunctionPrototype> {
 	tokens.next(); // Consume TokenKind::Extern
-	parse_function_prototype(tokens)
+	parse_function_prototype(tokens, doc)
 }
 
 fn parse_function_prototype(
-	tokens: &mut Peekable<impl Iterator<Item = Token>>,
+	tokens: &mut TokenStream<impl Iterator<Item = Token>>,
+	doc: Option<Comment>,
 ) -> Result<ast::statement::FunctionPrototype> {
-	let name = helper::parse_identifier(tokens.next())?;
-	let args = parse_function_argument_list(tokens)?;
+	let name = helper::parse_name(tokens)?;
+	let (args, comments) = parse_function_argument_list(tokens)?;
 	let return_type = parse_function_prototype_return_type(tokens)?;
-	Ok(ast::statement::FunctionPrototype { name, args, return_type })
+	Ok(ast::statement::FunctionPrototype { doc, name, args, comments, return_type })
 }
 
 fn parse_function_argument_list(
-	tokens: &mut Peekable<impl Iterator<Item = Token>>,
-) -> Result<Vec<ast::statement::FunctionArgument>> {
-	helper::parse_opening_parenthesis(tokens.next())?;
-	let mut arguments: Vec<ast::statement::FunctionArgument> = Vec::new();
-
-	// Check whether the argument list is empty, i.e. whether the next token is a closing parenthesis
-	if let Some(Token { value: TokenKind::ClosingParentheses, .. }) = tokens.peek() {
-		tokens.next(); // Consume the closing parenthesis
-		return Ok(arguments);
-	}
-
-	// Collect all arguments until closing parentheses
-	loop {
-		let name = helper::parse_identifier(tokens.next())?;
-		helper::parse_colon(tokens.next())?;
+	tokens: &mut TokenStream<impl Iterator<Item = Token>>,
+) -> Result<(Vec<Commented<ast::statement::FunctionArgument>>, Vec<Comment>)> {
+	helper::parse_parenthesized_list(tokens, |tokens| {
+		let name = helper::parse_name(tokens)?;
+		helper::parse_colon(tokens)?;
 		let data_type = variable::parse_data_type(tokens)?;
-		arguments.push(ast::statement::FunctionArgument { name, data_type });
-		match tokens.peek() {
-			Some(Token { value: TokenKind::Comma, .. }) => {
-				tokens.next(); // Consume the comma
-			},
-			_ => break, // No comma after this argument, so this is the last argument
-		}
-	}
-	helper::parse_closing_parenthesis(tokens.next())?;
-	Ok(arguments)
+		Ok(ast::statement::FunctionArgument { name, data_type })
+	})
 }
 
 fn parse_function_prototype_return_type(
-	tokens: &mut Peekable<impl Iterator<Item = Token>>,
+	tokens: &mut TokenStream<impl Iterator<Item = Token>>,
 ) -> Result<Option<PositionContainer<ast::statement::DataType>>> {
 	match tokens.peek() {
 		// No return type specified
@@ -80,34 +64,15 @@ fn parse_function_prototype_return_type(
 }
 
 pub(crate) fn parse_function_call(
-	tokens: &mut Peekable<impl Iterator<Item = Token>>,
+	tokens: &mut TokenStream<impl Iterator<Item = Token>>,
 	identifier: PositionContainer<String>,
 ) -> Result<ast::expression::FunctionCall> {
-	Ok(ast::expression::FunctionCall { name: identifier, params: parse_function_parameters(tokens)? })
+	let (params, comments) = parse_function_parameters(tokens)?;
+	Ok(ast::expression::FunctionCall { name: identifier, params, comments })
 }
 
-fn parse_function_parameters(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Result<Vec<Expression>> {
-	helper::parse_opening_parenthesis(tokens.next())?;
-	let mut parameters: Vec<Expression> = Vec::new();
-
-	// Check whether the parameter list is empty, i.e. whether the next token is a closing parenthesis
-	if let Some(Token { value: TokenKind::ClosingParentheses, .. }) = tokens.peek() {
-		tokens.next(); // Consume the closing parenthesis
-		return Ok(parameters);
-	}
-
-	// Collect all parameters until closing parentheses
-	loop {
-		let parameter = parse_primary_expression(tokens)?;
-		parameters.push(parameter);
-		match tokens.peek() {
-			Some(Token { value: TokenKind::Comma, .. }) => {
-				tokens.next(); // Consume the comma
-			},
-			_ => break, // No comma after this parameter, so this is the last parameter
-		}
-	}
-
-	helper::parse_closing_parenthesis(tokens.next())?;
-	Ok(parameters)
+fn parse_function_parameters(
+	tokens: &mut TokenStream<impl Iterator<Item = Token>>,
+) -> Result<(Vec<Commented<Expression>>, Vec<Comment>)> {
+	helper::parse_parenthesized_list(tokens, parse_binary_expression)
 }
@@ -1,119 +1,277 @@
-use super::{Error, Result};
+use std::mem;
+
+use super::{Error, Result, TokenStream};
 use crate::{
-	ast::expression::BinaryOperator,
-	source::PositionContainer,
+	ast::{expression::BinaryOperator, Comment, Commented, Comments},
+	lexer,
+	source::{PositionContainer, SourcePositionRange},
 	token::{Token, TokenKind},
 };
 
-pub(crate) fn parse_identifier(token: Option<Token>) -> Result<PositionContainer<String>> {
-	match token {
-		Some(Token { position, value: TokenKind::Identifier(ident) }) => Ok(PositionContainer::new(ident, position)),
-		_ => Err(Error::ExpectedToken { expected: TokenKind::Identifier(String::new()), found: token }),
+pub(crate) fn parse_identifier(
+	tokens: &mut TokenStream<impl Iterator<Item = Token>>,
+) -> Result<PositionContainer<String>> {
+	match tokens.next() {
+		Some(Token { span, value: TokenKind::Identifier(ident) }) => {
+			Ok(PositionContainer::new(ident, tokens.position_of(span)))
+		},
+		found => {
+			Err(Error::ExpectedToken { expected: TokenKind::Identifier(String::new()), found: tokens.locate(found) })
+		},
 	}
 }
 
-pub(crate) fn parse_opening_parenthesis(token: Option<Token>) -> Result<()> {
-	match token.as_deref() {
-		Some(TokenKind::OpeningParentheses) => Ok(()),
-		_ => Err(Error::ExpectedToken { expected: TokenKind::OpeningParentheses, found: token }),
+/// Parses the name of a new variable, function, struct or field, which must not be a
+/// [reserved keyword](crate::lexer::RESERVED_KEYWORDS).
+pub(crate) fn parse_name(tokens: &mut TokenStream<impl Iterator<Item = Token>>) -> Result<PositionContainer<String>> {
+	let name = parse_identifier(tokens)?;
+	if lexer::is_reserved_keyword(&name) {
+		return Err(Error::ReservedKeyword(name));
 	}
+	Ok(name)
 }
 
-pub(crate) fn parse_closing_parenthesis(token: Option<Token>) -> Result<()> {
-	match token.as_deref() {
-		Some(TokenKind::ClosingParentheses) => Ok(()),
-		_ => Err(Error::ExpectedToken { expected: TokenKind::ClosingParentheses, found: token }),
+pub(crate) fn parse_opening_parenthesis(tokens: &mut TokenStream<impl Iterator<Item = Token>>) -> Result<()> {
+	match tokens.next() {
+		Some(Token { value: TokenKind::OpeningParentheses, .. }) => Ok(()),
+		found => Err(Error::ExpectedToken { expected: TokenKind::OpeningParentheses, found: tokens.locate(found) }),
 	}
 }
 
-pub(crate) fn parse_colon(token: Option<Token>) -> Result<()> {
-	match token.as_deref() {
-		Some(TokenKind::Colon) => Ok(()),
-		_ => Err(Error::ExpectedToken { expected: TokenKind::Colon, found: token }),
+pub(crate) fn parse_closing_parenthesis(tokens: &mut TokenStream<impl Iterator<Item = Token>>) -> Result<()> {
+	match tokens.next() {
+		Some(Token { value: TokenKind::ClosingParentheses, .. }) => Ok(()),
+		found => Err(Error::ExpectedToken { expected: TokenKind::ClosingParentheses, found: tokens.locate(found) }),
 	}
 }
 
-pub(crate) fn parse_comma(token: Option<Token>) -> Result<()> {
-	match token.as_deref() {
-		Some(TokenKind::Comma) => Ok(()),
-		_ => Err(Error::ExpectedToken { expected: TokenKind::Comma, found: token }),
+pub(crate) fn parse_colon(tokens: &mut TokenStream<impl Iterator<Item = Token>>) -> Result<()> {
+	match tokens.next() {
+		Some(Token { value: TokenKind::Colon, .. }) => Ok(()),
+		found => Err(Error::ExpectedToken { expected: TokenKind::Colon, found: tokens.locate(found) }),
 	}
 }
 
-pub(crate) fn parse_semicolon(token: Option<Token>) -> Result<()> {
-	match token.as_deref() {
-		Some(TokenKind::Semicolon) => Ok(()),
-		_ => Err(Error::ExpectedToken { expected: TokenKind::Semicolon, found: token }),
+pub(crate) fn parse_semicolon(tokens: &mut TokenStream<impl Iterator<Item = Token>>) -> Result<()> {
+	match tokens.next() {
+		Some(Token { value: TokenKind::Semicolon, .. }) => Ok(()),
+		found => Err(Error::ExpectedToken { expected: TokenKind::Semicolon, found: tokens.locate(found) }),
 	}
 }
 
-pub(crate) fn parse_opening_curly_parenthesis(token: Option<Token>) -> Result<()> {
-	match token.as_deref() {
-		Some(TokenKind::OpeningCurlyBraces) => Ok(()),
-		_ => Err(Error::ExpectedToken { expected: TokenKind::OpeningCurlyBraces, found: token }),
+pub(crate) fn parse_end_of_line(tokens: &mut TokenStream<impl Iterator<Item = Token>>) -> Result<()> {
+	match tokens.next() {
+		Some(Token { value: TokenKind::EndOfLine, .. }) => Ok(()),
+		found => Err(Error::ExpectedToken { expected: TokenKind::EndOfLine, found: tokens.locate(found) }),
 	}
 }
 
-pub(crate) fn parse_closing_curly_parenthesis(token: Option<Token>) -> Result<()> {
-	match token.as_deref() {
-		Some(TokenKind::ClosingCurlyBraces) => Ok(()),
-		_ => Err(Error::ExpectedToken { expected: TokenKind::ClosingCurlyBraces, found: token }),
+/// Parses the end of a statement, i.e. a newline or `;`.
+///
+/// The end of the enclosing block or file also ends a statement, so `}` or nothing are accepted without being
+/// consumed.
+pub(crate) fn parse_statement_end(tokens: &mut TokenStream<impl Iterator<Item = Token>>) -> Result<()> {
+	match tokens.peek().map(|token| &token.value) {
+		Some(TokenKind::Semicolon) => parse_semicolon(tokens),
+		Some(TokenKind::ClosingCurlyBraces) | None => Ok(()),
+		_ => parse_end_of_line(tokens),
 	}
 }
 
-pub(crate) fn parse_variable_declaration(token: Option<Token>) -> Result<()> {
-	match token.as_deref() {
-		Some(TokenKind::Var) => Ok(()),
-		_ => Err(Error::ExpectedToken { expected: TokenKind::Var, found: token }),
+/// Parses consecutive comments, e.g. the comments before or after a node.
+///
+/// Doc comments are taken as normal comments here, since there is no function, struct or field they could document.
+pub(crate) fn parse_comments(tokens: &mut TokenStream<impl Iterator<Item = Token>>) -> Vec<Comment> {
+	let mut comments = Vec::new();
+	while let Some(token) =
+		tokens.next_if(|token| matches!(token.value, TokenKind::Comment(_) | TokenKind::DocComment(_)))
+	{
+		match token.value {
+			TokenKind::Comment(comment) => comments.push(Comment::new(comment, tokens.position_of(token.span))),
+			TokenKind::DocComment(comment) => {
+				let comment = Comment::new(comment, tokens.position_of(token.span));
+				tracing::warn!("Doc comment {} does not document a function, struct or field", comment);
+				comments.push(comment);
+			},
+			_ => unreachable!("Only comments are taken"),
+		}
 	}
+	comments
 }
 
-pub(crate) fn parse_equal(token: Option<Token>) -> Result<()> {
-	match token.as_deref() {
-		Some(TokenKind::Equal) => Ok(()),
-		_ => Err(Error::ExpectedToken { expected: TokenKind::Equal, found: token }),
+/// Parses a single comment, but not a doc comment.
+pub(crate) fn parse_comment(tokens: &mut TokenStream<impl Iterator<Item = Token>>) -> Option<Comment> {
+	match tokens.next_if(|token| matches!(token.value, TokenKind::Comment(_)))? {
+		Token { span, value: TokenKind::Comment(comment) } => Some(Comment::new(comment, tokens.position_of(span))),
+		_ => unreachable!("Only comments are taken"),
 	}
 }
 
-pub(crate) fn parse_if(token: Option<Token>) -> Result<()> {
-	match token.as_deref() {
-		Some(TokenKind::If) => Ok(()),
-		_ => Err(Error::ExpectedToken { expected: TokenKind::If, found: token }),
+/// Adds a doc comment that does not document anything to the `comments`, at its place in the source code.
+pub(crate) fn keep_stray_doc(comments: &mut Vec<Comment>, doc: Option<Comment>) {
+	if let Some(doc) = doc {
+		let index = comments.partition_point(|comment| comment.position.span.start < doc.position.span.start);
+		comments.insert(index, doc);
 	}
 }
 
-pub(crate) fn parse_struct(token: Option<Token>) -> Result<()> {
-	match token.as_deref() {
-		Some(TokenKind::Struct) => Ok(()),
-		_ => Err(Error::ExpectedToken { expected: TokenKind::Struct, found: token }),
+/// Parses a `##` doc comment.
+pub(crate) fn parse_doc_comment(tokens: &mut TokenStream<impl Iterator<Item = Token>>) -> Option<Comment> {
+	match tokens.next_if(|token| matches!(token.value, TokenKind::DocComment(_)))? {
+		Token { span, value: TokenKind::DocComment(doc) } => Some(Comment::new(doc, tokens.position_of(span))),
+		_ => unreachable!("Only doc comments are taken"),
 	}
 }
 
-pub(crate) fn parse_while(token: Option<Token>) -> Result<()> {
-	match token.as_deref() {
-		Some(TokenKind::While) => Ok(()),
-		_ => Err(Error::ExpectedToken { expected: TokenKind::While, found: token }),
+/// Parses a list of elements enclosed by parentheses and separated by commas, e.g. the arguments of a function.
+///
+/// Comments before an element are its leading comments. Comments after an element on the same line, as well as all
+/// comments after the last element, are its trailing comments. Comments in an empty list have no element to be
+/// attached to, so they are returned next to the elements for the node owning the list.
+pub(crate) fn parse_parenthesized_list<T, I: Iterator<Item = Token>>(
+	tokens: &mut TokenStream<I>,
+	mut parse_element: impl FnMut(&mut TokenStream<I>) -> Result<T>,
+) -> Result<(Vec<Commented<T>>, Vec<Comment>)> {
+	parse_opening_parenthesis(tokens)?;
+	let mut elements = Vec::new();
+	let mut leading = parse_comments(tokens);
+
+	// Check whether the list is empty, i.e. whether the next token is a closing parenthesis
+	if let Some(Token { value: TokenKind::ClosingParentheses, .. }) = tokens.peek() {
+		tokens.next(); // Consume the closing parenthesis
+		return Ok((elements, leading));
 	}
-}
 
-pub(crate) fn parse_operator(token: Option<Token>) -> Result<PositionContainer<BinaryOperator>> {
-	match token {
-		Some(token) => Ok(PositionContainer {
-			position: token.position.clone(),
-			value: match token.value {
-				TokenKind::Plus => BinaryOperator::Add,
-				TokenKind::Minus => BinaryOperator::Subtract,
-				TokenKind::Star => BinaryOperator::Multiply,
-				TokenKind::Slash => BinaryOperator::Divide,
-				TokenKind::Equal => BinaryOperator::Equal,
-				TokenKind::NotEqual => BinaryOperator::NotEqual,
-				TokenKind::Less => BinaryOperator::Less,
-				// TokenKind::LessEqual => BinaryOperator::LessEqual,
-				TokenKind::Greater => BinaryOperator::Greater,
-				// TokenKind::GreaterEqual => BinaryOperator::GreaterEqual,
-				_ => return Err(Error::ExpectedToken { expected: TokenKind::Plus, found: Some(token) }),
+	// Collect all elements until closing parentheses
+	loop {
+		let element = parse_element(tokens)?;
+		// Comments inside the element which were not attached to any of its nodes trail the element
+		let mut trailing = tokens.take_comments();
+		trailing.extend(parse_comments(tokens));
+		let mut comments = Comments { leading: mem::take(&mut leading), trailing, following: Vec::new() };
+		match tokens.next_if(|token| token.value == TokenKind::Comma) {
+			Some(comma) => {
+				// Comments on the line of the comma belong to this element, the others to the next element
+				let comma_line = tokens.position_of(comma.span).position().end.line;
+				let (trailing, next_leading) = parse_comments(tokens)
+					.into_iter()
+					.partition(|comment| comment.position.position().start.line == comma_line);
+				comments.trailing.extend::<Vec<Comment>>(trailing);
+				leading = next_leading;
+				elements.push(Commented::new(element, comments));
+			},
+			None => {
+				// No comma after this element, so this is the last element
+				elements.push(Commented::new(element, comments));
+				break;
 			},
-		}),
-		None => Err(Error::IllegalToken { token, context: "operator" }),
+		}
+	}
+	parse_closing_parenthesis(tokens)?;
+	Ok((elements, Vec::new()))
+}
+
+pub(crate) fn parse_opening_curly_parenthesis(tokens: &mut TokenStream<impl Iterator<Item = Token>>) -> Result<()> {
+	match tokens.next() {
+		Some(Token { value: TokenKind::OpeningCurlyBraces, .. }) => Ok(()),
+		found => Err(Error::ExpectedToken { expected: TokenKind::OpeningCurlyBraces, found: tokens.locate(found) }),
+	}
+}
+
+pub(crate) fn parse_variable_declaration(tokens: &mut TokenStream<impl Iterator<Item = Token>>) -> Result<()> {
+	match tokens.next() {
+		Some(Token { value: TokenKind::Var, .. }) => Ok(()),
+		found => Err(Error::ExpectedToken { expected: TokenKind::Var, found: tokens.locate(found) }),
+	}
+}
+
+pub(crate) fn parse_equal(tokens: &mut TokenStream<impl Iterator<Item = Token>>) -> Result<()> {
+	match tokens.next() {
+		Some(Token { value: TokenKind::Equal, .. }) => Ok(()),
+		found => Err(Error::ExpectedToken { expected: TokenKind::Equal, found: tokens.locate(found) }),
+	}
+}
+
+pub(crate) fn parse_if(tokens: &mut TokenStream<impl Iterator<Item = Token>>) -> Result<()> {
+	match tokens.next() {
+		Some(Token { value: TokenKind::If, .. }) => Ok(()),
+		found => Err(Error::ExpectedToken { expected: TokenKind::If, found: tokens.locate(found) }),
+	}
+}
+
+pub(crate) fn parse_struct(tokens: &mut TokenStream<impl Iterator<Item = Token>>) -> Result<()> {
+	match tokens.next() {
+		Some(Token { value: TokenKind::Struct, .. }) => Ok(()),
+		found => Err(Error::ExpectedToken { expected: TokenKind::Struct, found: tokens.locate(found) }),
 	}
 }
+
+pub(crate) fn parse_while(tokens: &mut TokenStream<impl Iterator<Item = Token>>) -> Result<()> {
+	match tokens.next() {
+		Some(Token { value: TokenKind::While, .. }) => Ok(()),
+		found => Err(Error::ExpectedToken { expected: TokenKind::While, found: tokens.locate(found) }),
+	}
+}
+
+pub(crate) fn parse_for(tokens: &mut TokenStream<impl Iterator<Item = Token>>) -> Result<()> {
+	match tokens.next() {
+		Some(Token { value: TokenKind::For, .. }) => Ok(()),
+		found => Err(Error::ExpectedToken { expected: TokenKind::For, found: tokens.locate(found) }),
+	}
+}
+
+pub(crate) fn parse_new(tokens: &mut TokenStream<impl Iterator<Item = Token>>) -> Result<()> {
+	match tokens.next() {
+		Some(Token { value: TokenKind::New, .. }) => Ok(()),
+		found => Err(Error::ExpectedToken { expected: TokenKind::New, found: tokens.locate(found) }),
+	}
+}
+
+/// Parses an `alloc` and returns its position.
+pub(crate) fn parse_alloc(tokens: &mut TokenStream<impl Iterator<Item = Token>>) -> Result<SourcePositionRange> {
+	match tokens.next() {
+		Some(Token { value: TokenKind::Alloc, span }) => Ok(tokens.position_of(span)),
+		found => Err(Error::ExpectedToken { expected: TokenKind::Alloc, found: tokens.locate(found) }),
+	}
+}
+
+pub(crate) fn parse_less(tokens: &mut TokenStream<impl Iterator<Item = Token>>) -> Result<()> {
+	match tokens.next() {
+		Some(Token { value: TokenKind::Less, .. }) => Ok(()),
+		found => Err(Error::ExpectedToken { expected: TokenKind::Less, found: tokens.locate(found) }),
+	}
+}
+
+pub(crate) fn parse_greater(tokens: &mut TokenStream<impl Iterator<Item = Token>>) -> Result<()> {
+	match tokens.next() {
+		Some(Token { value: TokenKind::Greater, .. }) => Ok(()),
+		found => Err(Error::ExpectedToken { expected: TokenKind::Greater, found: tokens.locate(found) }),
+	}
+}
+
+/// Returns the binary operator denoted by `token`, or `None` if `token` is no binary operator.
+pub(crate) fn binary_operator(token: &TokenKind) -> Option<BinaryOperator> {
+	let operator = match token {
+		TokenKind::Plus => BinaryOperator::Add,
+		TokenKind::Minus => BinaryOperator::Subtract,
+		TokenKind::Star => BinaryOperator::Multiply,
+		TokenKind::Slash => BinaryOperator::Divide,
+		TokenKind::Modulus => BinaryOperator::Modulus,
+		TokenKind::BitOr => BinaryOperator::BitOr,
+		TokenKind::BitAnd => BinaryOperator::BitAnd,
+		TokenKind::BitXor => BinaryOperator::BitXor,
+		TokenKind::ShiftLeft => BinaryOperator::ShiftLeft,
+		TokenKind::ShiftRight => BinaryOperator::ShiftRight,
+		TokenKind::DoubleEqual => BinaryOperator::Equal,
+		TokenKind::NotEqual => BinaryOperator::NotEqual,
+		TokenKind::Less => BinaryOperator::Less,
+		TokenKind::LessEqual => BinaryOperator::LessEqual,
+		TokenKind::Greater => BinaryOperator::Greater,
+		TokenKind::GreaterEqual => BinaryOperator::GreaterEqual,
+		TokenKind::And => BinaryOperator::And,
+		TokenKind::Or => BinaryOperator::Or,
+		TokenKind::Xor => BinaryOperator::Xor,
+		_ => return None,
+	};
+	Some(operator)
+}
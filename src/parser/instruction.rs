@@ -1,83 +1,218 @@
-use std::iter::Peekable;
-
-use super::Result;
+use super::{Result, TokenStream};
 use crate::{
 	ast,
 	ast::Statement,
 	parser::{
-		block::parse_block,
-		expression,
-		expression::{parse_float, parse_int, parse_parentheses},
-		function::parse_function_call,
-		helper,
-		variable::parse_variable_declaration,
+		block::parse_block, expression, function::parse_function_call, helper, variable::parse_variable_declaration,
 		Error,
 	},
+	source::PositionContainer,
 	token::{Token, TokenKind},
 };
 
-pub fn parse_instruction(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Result<ast::Instruction> {
+pub fn parse_instruction(
+	tokens: &mut TokenStream<impl Iterator<Item = Token>>,
+	errors: &mut Vec<Error>,
+) -> Result<ast::Instruction> {
 	match tokens.peek() {
-		Some(Token { value: TokenKind::Identifier(_), .. }) => Ok(parse_identifier_instruction(tokens)?),
-		Some(Token { value: TokenKind::Float(_), .. }) => {
-			Ok(ast::Instruction::Expression(ast::Expression::Number(parse_float(tokens)?)))
+		Some(Token { value: TokenKind::Identifier(_), .. }) => Ok(parse_identifier_instruction(tokens, errors)?),
+		Some(Token {
+			value:
+				TokenKind::Float(..)
+				| TokenKind::Int(..)
+				| TokenKind::StringLiteral(_)
+				| TokenKind::OpeningParentheses
+				| TokenKind::New
+				| TokenKind::Alloc
+				| TokenKind::Nil
+				| TokenKind::Minus
+				| TokenKind::Not
+				| TokenKind::BitNot
+				| TokenKind::Reference
+				| TokenKind::Dereference,
+			..
+		}) => {
+			// E.g. a dereferenced pointer like `deref p` or `(deref p)@3`, which is either assigned or the start of
+			// an expression
+			let target = expression::parse_primary_expression(tokens)?;
+			parse_assignment_or_expression(target, tokens)
 		},
-		Some(Token { value: TokenKind::Int(_), .. }) => {
-			Ok(ast::Instruction::Expression(ast::Expression::Number(parse_int(tokens)?)))
+		Some(Token { value: TokenKind::Delete, .. }) => {
+			tokens.next(); // Consume the TokenKind::Delete
+			Ok(ast::Instruction::Statement(Statement::Delete(expression::parse_binary_expression(tokens)?)))
 		},
-		Some(Token { value: TokenKind::OpeningParentheses, .. }) => {
-			Ok(ast::Instruction::Expression(parse_parentheses(tokens)?))
+		Some(Token { value: TokenKind::If, .. }) => {
+			Ok(ast::Instruction::IfElse(Box::new(parse_if_else(tokens, errors)?)))
 		},
-		Some(Token { value: TokenKind::If, .. }) => Ok(ast::Instruction::IfElse(Box::new(parse_if_else(tokens)?))),
 		Some(Token { value: TokenKind::While, .. }) => {
-			Ok(ast::Instruction::WhileLoop(Box::new(parse_while_loop(tokens)?)))
+			Ok(ast::Instruction::WhileLoop(Box::new(parse_while_loop(tokens, None, errors)?)))
+		},
+		Some(Token { value: TokenKind::For, .. }) => {
+			Ok(ast::Instruction::ForLoop(Box::new(parse_for_loop(tokens, None, errors)?)))
+		},
+		Some(Token { value: TokenKind::Break, .. }) => {
+			Ok(ast::Instruction::Statement(Statement::Break(parse_jump(tokens)?)))
+		},
+		Some(Token { value: TokenKind::Continue, .. }) => {
+			Ok(ast::Instruction::Statement(Statement::Continue(parse_jump(tokens)?)))
 		},
 		Some(Token { value: TokenKind::Var, .. }) => {
 			Ok(ast::Instruction::Statement(Statement::VariableDeclaration(parse_variable_declaration(tokens)?)))
 		},
 		Some(Token { value: TokenKind::Return, .. }) => {
-			tokens.next(); // Consume the TokenKind::Return
-			Ok(ast::Instruction::Statement(Statement::Return(expression::parse_binary_expression(tokens)?)))
+			Ok(ast::Instruction::Statement(Statement::Return(parse_return(tokens)?)))
 		},
-		other => Err(Error::IllegalToken { token: other.cloned(), context: "instruction" }),
+		_ => Err(Error::IllegalToken { token: tokens.peek_located(), context: "instruction" }),
 	}
 }
 
-pub fn parse_if_else(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Result<ast::IfElse> {
-	helper::parse_if(tokens.next())?;
+pub fn parse_if_else(
+	tokens: &mut TokenStream<impl Iterator<Item = Token>>,
+	errors: &mut Vec<Error>,
+) -> Result<ast::IfElse> {
+	helper::parse_if(tokens)?;
 	let condition = expression::parse_binary_expression(tokens)?;
-	let if_true = parse_block(tokens)?;
-	let if_false = match tokens.peek() {
-		Some(Token { value: TokenKind::Else, .. }) => {
-			tokens.next(); // Consume the TokenKind::Else
-			parse_block(tokens)?
+	let if_true = parse_block(tokens, errors)?;
+	let mut else_ifs = Vec::new();
+	let mut if_false = ast::Block::default();
+	while tokens.next_if(|token| token.value == TokenKind::Else).is_some() {
+		// `else if` continues the chain, a plain `else` ends it
+		if tokens.next_if(|token| token.value == TokenKind::If).is_none() {
+			if_false = parse_block(tokens, errors)?;
+			break;
+		}
+		let condition = expression::parse_binary_expression(tokens)?;
+		let body = parse_block(tokens, errors)?;
+		else_ifs.push(ast::ElseIf { condition, body });
+	}
+
+	Ok(ast::IfElse { condition, if_true, else_ifs, if_false })
+}
+
+pub fn parse_while_loop(
+	tokens: &mut TokenStream<impl Iterator<Item = Token>>,
+	label: Option<PositionContainer<String>>,
+	errors: &mut Vec<Error>,
+) -> Result<ast::WhileLoop> {
+	helper::parse_while(tokens)?;
+	let condition = expression::parse_binary_expression(tokens)?;
+	let body = parse_block(tokens, errors)?;
+	Ok(ast::WhileLoop { label, condition, body })
+}
+
+pub fn parse_for_loop(
+	tokens: &mut TokenStream<impl Iterator<Item = Token>>,
+	label: Option<PositionContainer<String>>,
+	errors: &mut Vec<Error>,
+) -> Result<ast::ForLoop> {
+	helper::parse_for(tokens)?;
+	let variable = helper::parse_name(tokens)?;
+	let iterable = match tokens.next() {
+		Some(Token { value: TokenKind::In, .. }) => ast::for_loop::Iterable::Range(Box::new(parse_range(tokens)?)),
+		Some(Token { value: TokenKind::Of, .. }) => {
+			ast::for_loop::Iterable::Array(expression::parse_binary_expression(tokens)?)
 		},
-		_ => Vec::new(),
+		other => return Err(Error::IllegalToken { token: tokens.locate(other), context: "for loop" }),
+	};
+	let body = parse_block(tokens, errors)?;
+	Ok(ast::ForLoop { label, variable, iterable, body })
+}
+
+/// Parses a range like `0..n`, `0..=n` or `0..n step 2`.
+fn parse_range(tokens: &mut TokenStream<impl Iterator<Item = Token>>) -> Result<ast::for_loop::Range> {
+	let start = expression::parse_binary_expression(tokens)?;
+	let inclusive = match tokens.next() {
+		Some(Token { value: TokenKind::DoubleDot, .. }) => false,
+		Some(Token { value: TokenKind::DoubleDotEqual, .. }) => true,
+		other => return Err(Error::IllegalToken { token: tokens.locate(other), context: "range" }),
 	};
+	let end = expression::parse_binary_expression(tokens)?;
+	let step = match tokens.next_if(|token| token.value == TokenKind::Step) {
+		Some(_) => Some(expression::parse_binary_expression(tokens)?),
+		None => None,
+	};
+	Ok(ast::for_loop::Range { start, end, inclusive, step })
+}
 
-	Ok(ast::IfElse { condition, if_true, if_false })
+/// Parses a `break` or `continue` with an optional label.
+fn parse_jump(tokens: &mut TokenStream<impl Iterator<Item = Token>>) -> Result<ast::statement::Jump> {
+	// The caller made sure that the token is a `break` or `continue`
+	let span = tokens.next().unwrap().span;
+	let position = tokens.position_of(span);
+	let label = match tokens.peek() {
+		Some(Token { value: TokenKind::Identifier(_), .. }) => Some(helper::parse_identifier(tokens)?),
+		_ => None,
+	};
+	Ok(ast::statement::Jump { position, label })
 }
 
-pub fn parse_while_loop(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Result<ast::WhileLoop> {
-	helper::parse_while(tokens.next())?;
-	let condition = expression::parse_binary_expression(tokens)?;
-	let body = parse_block(tokens)?;
-	Ok(ast::WhileLoop { condition, body })
+/// Parses a `return`, whose value is left out if the statement ends right after the keyword.
+fn parse_return(tokens: &mut TokenStream<impl Iterator<Item = Token>>) -> Result<ast::statement::Return> {
+	// The caller made sure that the token is a `return`
+	let span = tokens.next().unwrap().span;
+	let position = tokens.position_of(span);
+	let value = match tokens.peek().map(|token| &token.value) {
+		None
+		| Some(
+			TokenKind::EndOfLine
+			| TokenKind::Semicolon
+			| TokenKind::ClosingCurlyBraces
+			| TokenKind::Comment(_)
+			| TokenKind::DocComment(_),
+		) => None,
+		Some(_) => Some(expression::parse_binary_expression(tokens)?),
+	};
+	Ok(ast::statement::Return { position, value })
 }
 
-pub fn parse_identifier_instruction(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Result<ast::Instruction> {
-	let identifier = helper::parse_identifier(tokens.next())?;
+/// Parses the loop after a label like `outer:`.
+fn parse_labeled_loop(
+	tokens: &mut TokenStream<impl Iterator<Item = Token>>,
+	label: PositionContainer<String>,
+	errors: &mut Vec<Error>,
+) -> Result<ast::Instruction> {
+	helper::parse_colon(tokens)?;
 	match tokens.peek() {
+		Some(Token { value: TokenKind::While, .. }) => {
+			Ok(ast::Instruction::WhileLoop(Box::new(parse_while_loop(tokens, Some(label), errors)?)))
+		},
+		Some(Token { value: TokenKind::For, .. }) => {
+			Ok(ast::Instruction::ForLoop(Box::new(parse_for_loop(tokens, Some(label), errors)?)))
+		},
+		_ => Err(Error::IllegalToken { token: tokens.next_located(), context: "labeled loop" }),
+	}
+}
+
+pub fn parse_identifier_instruction(
+	tokens: &mut TokenStream<impl Iterator<Item = Token>>,
+	errors: &mut Vec<Error>,
+) -> Result<ast::Instruction> {
+	let identifier = helper::parse_identifier(tokens)?;
+	match tokens.peek() {
+		Some(Token { value: TokenKind::Colon, .. }) => parse_labeled_loop(tokens, identifier, errors),
 		Some(Token { value: TokenKind::OpeningParentheses, .. }) => {
-			Ok(ast::Instruction::Expression(ast::Expression::FunctionCall(parse_function_call(tokens, identifier)?)))
+			let function_call = ast::Expression::FunctionCall(parse_function_call(tokens, identifier)?);
+			let function_call = expression::parse_postfix_expressions(function_call, tokens)?;
+			Ok(ast::Instruction::Expression(expression::parse_binary_expression_rhs(function_call, 0, tokens)?))
 		},
-		Some(Token { value: TokenKind::Equal, .. }) => {
-			tokens.next(); // Consume the TokenKind::Equal
-			Ok(ast::Instruction::Statement(ast::Statement::VariableAssignment(ast::statement::VariableAssignment {
-				name: identifier,
-				value: expression::parse_binary_expression(tokens)?,
-			})))
+		_ => {
+			// Variable, field or array element, which is either assigned or the start of an expression
+			let target = expression::parse_postfix_expressions(ast::Expression::Variable(identifier), tokens)?;
+			parse_assignment_or_expression(target, tokens)
 		},
-		_ => Ok(ast::Instruction::Expression(ast::Expression::Variable(identifier))),
 	}
 }
+
+/// Parses an assignment to `target` if an `=` follows, or the rest of an expression starting with `target` otherwise.
+fn parse_assignment_or_expression(
+	target: ast::Expression,
+	tokens: &mut TokenStream<impl Iterator<Item = Token>>,
+) -> Result<ast::Instruction> {
+	if tokens.next_if(|token| token.value == TokenKind::Equal).is_none() {
+		return Ok(ast::Instruction::Expression(expression::parse_binary_expression_rhs(target, 0, tokens)?));
+	}
+	Ok(ast::Instruction::Statement(ast::Statement::VariableAssignment(ast::statement::VariableAssignment {
+		target,
+		value: expression::parse_binary_expression(tokens)?,
+	})))
+}
@@ -7,62 +7,137 @@ mod function;
 mod helper;
 mod instruction;
 mod struct_;
+#[cfg(test)]
+mod test;
+mod token_stream;
 mod variable;
 
-use std::iter::Peekable;
+use std::{mem, sync::Arc};
 
-pub use error::Error;
+pub use error::{Error, Errors};
+use token_stream::TokenStream;
 
 use crate::{
-	ast::Node,
+	ast::{Comment, Commented, Comments, Node},
 	parser::{
 		function::{parse_extern_function_declaration, parse_function_definition},
 		struct_::parse_struct_definition,
 	},
+	source::Source,
 	token::{Token, TokenKind},
 };
 
 pub type Result<T> = std::result::Result<T, Error>;
 
 /// Analyzes [`Token`]s and builds an [AST](crate::ast).
+///
+/// The parser recovers from syntax errors: After an error, it skips tokens until a synchronization point (the next
+/// `def`, `extern` or `struct`, or the closing `}` of the erroneous node) and continues parsing from there. Inside
+/// blocks, parsing resumes at the next instruction. This way, all syntax errors of a file can be reported at once.
 pub struct Parser<T>
 where
 	T: Iterator<Item = Token>,
 {
-	tokens: Peekable<T>,
+	tokens: TokenStream<T>,
+	/// Errors encountered so far.
+	errors: Vec<Error>,
 }
 
 impl<T> Parser<T>
 where
 	T: Iterator<Item = Token>,
 {
-	pub fn new(tokens: T) -> Self {
-		Self { tokens: tokens.peekable() }
+	/// Creates a parser for the `tokens` of `source`.
+	///
+	/// The tokens are located in `source` only when they become part of the AST or of an [`Error`].
+	pub fn new(tokens: T, source: Arc<Source>) -> Self {
+		Self { tokens: TokenStream::new(tokens, source), errors: Vec::new() }
 	}
-}
 
-fn parse_top_level_node(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Option<Result<Node>> {
-	let token = tokens.peek()?;
-	match **token {
-		TokenKind::Def => Some(parse_function_definition(tokens).map(Node::Function)),
-		TokenKind::Extern => Some(parse_extern_function_declaration(tokens).map(Node::FunctionPrototype)),
-		TokenKind::Struct => Some(parse_struct_definition(tokens).map(Node::Struct)),
-		TokenKind::Comment(ref comment) => {
-			tracing::warn!("Skipping {}", token);
-			tokens.next();
-			parse_top_level_node(tokens)
-		},
-		_ => Some(Err(Error::IllegalToken { token: Some(tokens.next()?), context: "top level node" })),
+	/// Parses all top level nodes.
+	///
+	/// Returns the (partial) AST, consisting of all nodes that could be parsed, together with all errors found.
+	/// Comments before a node and after it on the same line are attached to the node. Comments at the end of the file
+	/// are attached to the last node. The last doc comment before a node documents it.
+	pub fn parse(mut self) -> (Vec<Commented<Node>>, Vec<Error>) {
+		let mut nodes: Vec<Commented<Node>> = Vec::new();
+		let mut leading_comments = Vec::new();
+		let mut doc = None;
+		loop {
+			match self.tokens.peek().map(|token| &token.value) {
+				None => break,
+				// Empty line or empty statement
+				Some(TokenKind::EndOfLine | TokenKind::Semicolon) => {
+					self.tokens.next();
+					continue;
+				},
+				Some(TokenKind::Comment(_)) => {
+					leading_comments.extend(helper::parse_comment(&mut self.tokens));
+					continue;
+				},
+				Some(TokenKind::DocComment(_)) => {
+					// An earlier doc comment does not document anything and becomes a normal comment
+					let new_doc = helper::parse_doc_comment(&mut self.tokens);
+					helper::keep_stray_doc(&mut leading_comments, mem::replace(&mut doc, new_doc));
+					continue;
+				},
+				Some(_) => (),
+			}
+			match parse_top_level_node(&mut self.tokens, doc.take(), &mut self.errors) {
+				Ok(node) => {
+					let mut trailing = self.tokens.take_comments();
+					trailing.extend(helper::parse_comments(&mut self.tokens));
+					let comments =
+						Comments { leading: mem::take(&mut leading_comments), trailing, following: Vec::new() };
+					nodes.push(Commented::new(node, comments));
+				},
+				Err(err) => {
+					self.errors.push(err);
+					leading_comments.clear();
+					self.tokens.take_comments();
+					synchronize_top_level(&mut self.tokens);
+				},
+			}
+		}
+		// Comments at the end of the file
+		helper::keep_stray_doc(&mut leading_comments, doc);
+		match nodes.last_mut() {
+			Some(node) => node.comments.following.extend(leading_comments),
+			None => leading_comments.iter().for_each(|comment| tracing::warn!("Skipping {}", comment)),
+		}
+		(nodes, self.errors)
 	}
 }
 
-impl<T> Iterator for Parser<T>
-where
-	T: Iterator<Item = Token>,
-{
-	type Item = Result<Node>;
+fn parse_top_level_node(
+	tokens: &mut TokenStream<impl Iterator<Item = Token>>,
+	doc: Option<Comment>,
+	errors: &mut Vec<Error>,
+) -> Result<Node> {
+	match tokens.peek().map(|token| &token.value) {
+		Some(TokenKind::Def) => parse_function_definition(tokens, doc, errors).map(Node::Function),
+		Some(TokenKind::Extern) => parse_extern_function_declaration(tokens, doc).map(Node::FunctionPrototype),
+		Some(TokenKind::Struct) => parse_struct_definition(tokens, doc).map(Node::Struct),
+		_ => Err(Error::IllegalToken { token: tokens.next_located(), context: "top level node" }),
+	}
+}
 
-	fn next(&mut self) -> Option<Self::Item> {
-		parse_top_level_node(&mut self.tokens)
+/// Skips tokens until the start of the next top level node.
+///
+/// A `}` that closes the erroneous node is consumed, so that parsing continues after it.
+fn synchronize_top_level(tokens: &mut TokenStream<impl Iterator<Item = Token>>) {
+	let mut depth: usize = 0;
+	while let Some(token) = tokens.peek() {
+		match token.value {
+			TokenKind::Def | TokenKind::Extern | TokenKind::Struct => return,
+			TokenKind::OpeningCurlyBraces => depth += 1,
+			TokenKind::ClosingCurlyBraces if depth <= 1 => {
+				tokens.next(); // Consume the `}` closing the erroneous node
+				return;
+			},
+			TokenKind::ClosingCurlyBraces => depth -= 1,
+			_ => (),
+		}
+		tokens.next();
 	}
 }
@@ -1,33 +1,67 @@
-use std::iter::Peekable;
+use std::mem;
 
-use super::Result;
+use super::{Result, TokenStream};
 use crate::{
 	ast,
+	ast::{Comment, Commented, Comments},
 	parser::{helper, variable::parse_data_type},
 	token::{Token, TokenKind},
 };
 
 pub(crate) fn parse_struct_definition(
-	tokens: &mut Peekable<impl Iterator<Item = Token>>,
+	tokens: &mut TokenStream<impl Iterator<Item = Token>>,
+	doc: Option<Comment>,
 ) -> Result<ast::struct_::Struct> {
-	helper::parse_struct(tokens.next())?;
-	let name = helper::parse_identifier(tokens.next())?;
-	helper::parse_opening_curly_parenthesis(tokens.next())?;
-	let mut fields: Vec<ast::struct_::Field> = Vec::new();
+	helper::parse_struct(tokens)?;
+	let name = helper::parse_name(tokens)?;
+	helper::parse_opening_curly_parenthesis(tokens)?;
+	let mut fields: Vec<Commented<ast::struct_::Field>> = Vec::new();
+	let mut leading_comments = Vec::new();
+	let mut field_doc = None;
 	while let Some(token) = tokens.peek() {
-		if let TokenKind::ClosingCurlyBraces = **token {
-			tokens.next(); // Consume TokenKind::ClosingParentheses
-			break; // End of block
+		match **token {
+			TokenKind::ClosingCurlyBraces => {
+				tokens.next(); // Consume TokenKind::ClosingParentheses
+				break; // End of block
+			},
+			// Empty line
+			TokenKind::EndOfLine | TokenKind::Semicolon => {
+				tokens.next();
+			},
+			TokenKind::Comment(_) => leading_comments.extend(helper::parse_comment(tokens)),
+			// An earlier doc comment does not document anything and becomes a normal comment
+			TokenKind::DocComment(_) => {
+				let new_doc = helper::parse_doc_comment(tokens);
+				helper::keep_stray_doc(&mut leading_comments, mem::replace(&mut field_doc, new_doc));
+			},
+			_ => {
+				let field = parse_field(tokens, field_doc.take())?;
+				let comments = Comments {
+					leading: mem::take(&mut leading_comments),
+					trailing: helper::parse_comments(tokens),
+					following: Vec::new(),
+				};
+				fields.push(Commented::new(field, comments));
+				// Each field is ended by a newline or `;`
+				helper::parse_statement_end(tokens)?;
+			},
 		}
-		let field = parse_field(tokens)?;
-		fields.push(field);
 	}
-	Ok(ast::struct_::Struct { name, fields })
+	// Comments after the last field
+	helper::keep_stray_doc(&mut leading_comments, field_doc);
+	match fields.last_mut() {
+		Some(field) => field.comments.following.extend(leading_comments),
+		None => leading_comments.iter().for_each(|comment| tracing::warn!("Skipping {} in empty struct", comment)),
+	}
+	Ok(ast::struct_::Struct { doc, name, fields })
 }
 
-pub(crate) fn parse_field(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Result<ast::struct_::Field> {
-	let name = helper::parse_identifier(tokens.next())?;
-	helper::parse_colon(tokens.next())?;
+pub(crate) fn parse_field(
+	tokens: &mut TokenStream<impl Iterator<Item = Token>>,
+	doc: Option<Comment>,
+) -> Result<ast::struct_::Field> {
+	let name = helper::parse_name(tokens)?;
+	helper::parse_colon(tokens)?;
 	let data_type = parse_data_type(tokens)?;
-	Ok(ast::struct_::Field { name, data_type })
+	Ok(ast::struct_::Field { doc, name, data_type })
 }
@@ -0,0 +1,664 @@
+use std::sync::Arc;
+
+use crate::{
+	ast::{
+		expression::{NumberKind, StringPart},
+		for_loop::Iterable,
+		statement::Jump,
+		Commented, Comments, Expression, Instruction, Node, Statement,
+	},
+	lexer::Lexer,
+	parser::*,
+	source::{PositionContainer, Source},
+	token::TokenKind,
+};
+
+/// Tests that a syntax error inside a function body does not hide the following instructions and nodes.
+#[test]
+fn test_recover_inside_block() {
+	let (nodes, errors) = parse(
+		"def main() {
+			var a: int = 1
+			else
+			var b: int = 2
+		}
+		def other() {}",
+	);
+	assert_eq!(errors.len(), 1);
+	assert!(matches!(errors[0], Error::IllegalToken { token: Some(ref token), .. } if token.value == TokenKind::Else));
+
+	assert_eq!(nodes.len(), 2);
+	let Node::Function(ref main) = nodes[0].node else { panic!("Expected function, found {:?}", nodes[0]) };
+	assert_eq!(main.body.instructions.len(), 2);
+	assert!(main.body.instructions.iter().all(|instruction| matches!(instruction.node, Instruction::Statement(_))));
+}
+
+/// Tests that the parser resynchronizes on keywords starting an instruction on the same line as the error.
+#[test]
+fn test_recover_at_instruction_keyword() {
+	let (nodes, errors) =
+		parse("def main() {\n\tx = ) break\n\tx = ) continue\n\tx = ) for i in 0..1 {\n\t}\n\tx = ) del p\n}");
+	assert_eq!(errors.len(), 4);
+	let Node::Function(ref main) = nodes[0].node else { panic!("Expected function, found {:?}", nodes[0]) };
+	let instructions: Vec<&Instruction> = main.body.instructions.iter().map(|instruction| &instruction.node).collect();
+	assert!(matches!(
+		instructions[..],
+		[
+			Instruction::Statement(Statement::Break(_)),
+			Instruction::Statement(Statement::Continue(_)),
+			Instruction::ForLoop(_),
+			Instruction::Statement(Statement::Delete(_)),
+		]
+	));
+}
+
+/// Tests that the parser resynchronizes on the next top level node and reports every syntax error.
+#[test]
+fn test_recover_at_top_level() {
+	let (nodes, errors) = parse(
+		"def (x: int) { if x { x } }
+		struct { a: int }
+		extern exit(status: int)
+		}
+		def main() { }",
+	);
+	assert_eq!(errors.len(), 3);
+	assert!(matches!(errors[0], Error::ExpectedToken { expected: TokenKind::Identifier(_), .. }));
+	assert!(matches!(errors[1], Error::ExpectedToken { expected: TokenKind::Identifier(_), .. }));
+	assert!(
+		matches!(errors[2], Error::IllegalToken { token: Some(ref token), .. } if token.value == TokenKind::ClosingCurlyBraces)
+	);
+
+	assert_eq!(nodes.len(), 2);
+	assert!(matches!(nodes[0].node, Node::FunctionPrototype(_)));
+	assert!(matches!(nodes[1].node, Node::Function(_)));
+}
+
+/// Tests that a missing closing brace is reported and the function is still part of the AST.
+#[test]
+fn test_missing_closing_brace() {
+	let (nodes, errors) = parse(
+		"def main() {
+			var a: int = 1
+		def other() {}",
+	);
+	assert_eq!(errors.len(), 1);
+	assert!(matches!(errors[0], Error::ExpectedToken { expected: TokenKind::ClosingCurlyBraces, .. }));
+	assert_eq!(nodes.len(), 2);
+}
+
+/// Tests that unary operators bind stronger than binary operators.
+#[test]
+fn test_unary_expression() {
+	assert_eq!(expression_shape("-1"), "(- 1)");
+	assert_eq!(expression_shape("-a * b"), "(* (- a) b)");
+	assert_eq!(expression_shape("not a and b"), "(and (not a) b)");
+	assert_eq!(expression_shape("bitnot -a"), "(bitnot (- a))");
+	assert_eq!(expression_shape("-(a + b)"), "(- (+ a b))");
+}
+
+/// Tests that binary operators group according to their precedence and associativity.
+#[test]
+fn test_binary_expression_precedence() {
+	assert_eq!(expression_shape("a - b - c"), "(- (- a b) c)");
+	assert_eq!(expression_shape("a / b * c"), "(* (/ a b) c)");
+	assert_eq!(expression_shape("a - (b - c)"), "(- a (- b c))");
+	assert_eq!(expression_shape("a < b + c * d"), "(< a (+ b (* c d)))");
+	assert_eq!(expression_shape("a * b + c * d"), "(+ (* a b) (* c d))");
+	assert_eq!(expression_shape("a + b * c - d"), "(- (+ a (* b c)) d)");
+	assert_eq!(expression_shape("a * (b + c) * d"), "(* (* a (+ b c)) d)");
+	assert_eq!(expression_shape("a == b < c"), "(== a (< b c))");
+	assert_eq!(expression_shape("a < b == c > d"), "(== (< a b) (> c d))");
+	assert_eq!(expression_shape("a or b and c"), "(or a (and b c))");
+	assert_eq!(expression_shape("a and b or c and d"), "(or (and a b) (and c d))");
+	assert_eq!(expression_shape("a or b xor c and d"), "(or a (xor b (and c d)))");
+	assert_eq!(expression_shape("a and b == c or d"), "(or (and a (== b c)) d)");
+	assert_eq!(expression_shape("-a - -b * c"), "(- (- a) (* (- b) c))");
+}
+
+/// Tests the precedence of the bitwise operators and the modulus.
+#[test]
+fn test_bitwise_operator_precedence() {
+	assert_eq!(expression_shape("a bitor b bitxor c bitand d"), "(bitor a (bitxor b (bitand c d)))");
+	assert_eq!(expression_shape("a bitand b shl c + d"), "(bitand a (shl b (+ c d)))");
+	assert_eq!(expression_shape("a shl b shr c"), "(shr (shl a b) c)");
+	assert_eq!(expression_shape("a + b mod c % d"), "(+ a (mod (mod b c) d))");
+	assert_eq!(expression_shape("a bitor b == c bitand d"), "(== (bitor a b) (bitand c d))");
+	assert_eq!(expression_shape("a < b bitor c and d"), "(and (< a (bitor b c)) d)");
+}
+
+/// Tests that call arguments and variable initializers accept any expression.
+#[test]
+fn test_expressions_in_arguments_and_initializers() {
+	assert_eq!(expression_shape("b * 2"), "(* b 2)");
+	assert_eq!(expression_shape("add(1 + 2, x)"), "add((+ 1 2), x)");
+	assert_eq!(expression_shape("f(g(1, 2) * 3, h())"), "f((* g(1, 2) 3), h())");
+	assert_eq!(expression_shape("f(-x, not y, (a))"), "f((- x), (not y), a)");
+	assert_eq!(expression_shape("f(a < b and c, g(h(i(1))))"), "f((and (< a b) c), g(h(i(1))))");
+	assert_eq!(expression_shape("f(1) + f(2) * 3"), "(+ f(1) (* f(2) 3))");
+}
+
+/// Tests that expressions used as instructions are parsed completely.
+#[test]
+fn test_expression_instruction() {
+	let (nodes, errors) = parse("def main() { f(g(1, 2) * 3, h()) + 1; if a {}; (a) * 2 }");
+	assert_eq!(errors, Vec::new());
+	let Node::Function(ref function) = nodes[0].node else { panic!("Expected function, found {:?}", nodes[0]) };
+	let shapes: Vec<String> = function
+		.body
+		.instructions
+		.iter()
+		.filter_map(|instruction| match &instruction.node {
+			Instruction::Expression(expression) => Some(shape(expression)),
+			_ => None,
+		})
+		.collect();
+	assert_eq!(shapes, vec!["(+ f((* g(1, 2) 3), h()) 1)", "(* a 2)"]);
+}
+
+/// Tests that a newline ends a statement, unless the expression is incomplete.
+#[test]
+fn test_newline_terminates_statements() {
+	assert_eq!(instruction_shapes("a = b\n(c)"), vec!["a = b", "c"]);
+	assert_eq!(instruction_shapes("a = b\n-c"), vec!["a = b", "(- c)"]);
+	assert_eq!(instruction_shapes("a = b +\n\tc *\n\td"), vec!["a = (+ b (* c d))"]);
+	assert_eq!(instruction_shapes("a = b and\n\n\tnot c"), vec!["a = (and b (not c))"]);
+	assert_eq!(instruction_shapes("a = (b\n+ c)\n"), vec!["a = (+ b c)"]);
+	assert_eq!(instruction_shapes("x = 1; y = 2;\n\n;z = 3"), vec!["x = 1", "y = 2", "z = 3"]);
+	assert_eq!(instruction_shapes("if a {\n\tb\n} else {\n\tc\n}\nd"), vec!["if a else if []", "d"]);
+	assert_eq!(instruction_shapes("var x: int =\n\t1\nx"), vec!["var x = 1", "x"]);
+}
+
+/// Tests that `else if` chains are parsed flat, with an optional final `else`.
+#[test]
+fn test_else_if_chain() {
+	assert_eq!(
+		instruction_shapes("if a {\n} else if b {\n} else if c < 1 {\n} else {\n}\nd"),
+		vec!["if a else if [b, (< c 1)]", "d"]
+	);
+	assert_eq!(instruction_shapes("if a {\n} else if b {\n}\nd"), vec!["if a else if [b]", "d"]);
+	// `else` may start the line after the closing brace
+	assert_eq!(instruction_shapes("if a {\n}\nelse if b {\n}\n\nelse {\n}\nd"), vec!["if a else if [b]", "d"]);
+	assert_eq!(instruction_shapes("if a {\n}\nelsewhere = 1"), vec!["if a else if []", "elsewhere = 1"]);
+
+	let (nodes, errors) = parse("def main() {\n\tif a {\n\t\tx = 1\n\t} else if b {\n\t\tx = 2\n\t}\n}");
+	assert_eq!(errors, Vec::new());
+	let Node::Function(ref main) = nodes[0].node else { panic!("Expected function, found {:?}", nodes[0]) };
+	let Instruction::IfElse(ref if_else) = main.body.instructions[0].node else {
+		panic!("Expected if, found {:?}", main.body.instructions[0].node)
+	};
+	assert_eq!(if_else.else_ifs[0].body.instructions.len(), 1);
+	assert!(if_else.if_false.instructions.is_empty());
+}
+
+/// Tests that for loops over ranges and arrays are parsed.
+#[test]
+fn test_for_loop() {
+	assert_eq!(
+		instruction_shapes("for i in 0..n {\n}\nfor i in n - 1..=0 step -1 {\n}\nfor x of xs {\n\tx\n}"),
+		vec!["for i in 0..n", "for i in (- n 1)..=0 step (- 1)", "for x of xs"]
+	);
+
+	let (_, errors) = parse("def main() {\n\tfor i 0..n {\n\t}\n}");
+	assert!(matches!(
+		errors[0],
+		Error::IllegalToken { token: Some(ref token), context: "for loop" } if token.value == TokenKind::Int(0, None)
+	));
+}
+
+/// Tests that the value of a `return` may be left out at the end of a statement.
+#[test]
+fn test_return() {
+	assert_eq!(
+		instruction_shapes("return\nreturn; return 1 + 2\nif a {\n\treturn\n}\nreturn # done"),
+		vec!["return", "return", "return (+ 1 2)", "if a else if []", "return"]
+	);
+}
+
+/// Tests that loops can be labeled and that `break` and `continue` take an optional label.
+#[test]
+fn test_break_continue() {
+	assert_eq!(
+		instruction_shapes(
+			"outer: while a {\n}\ninner:\nfor i in 0..n {\n}\nbreak\ncontinue\nbreak outer\ncontinue inner"
+		),
+		vec!["outer: while a", "inner: for i in 0..n", "break", "continue", "break outer", "continue inner"]
+	);
+
+	// Only loops can be labeled
+	let (_, errors) = parse("def main() {\n\tlabel: if a {\n\t}\n}");
+	assert!(matches!(
+		errors[0],
+		Error::IllegalToken { token: Some(ref token), context: "labeled loop" } if token.value == TokenKind::If
+	));
+}
+
+/// Tests that field accesses bind stronger than operators and can be assigned to.
+#[test]
+fn test_field_access() {
+	assert_eq!(expression_shape("-p.age * 2"), "(* (- p.age) 2)");
+	assert_eq!(expression_shape("a.b.c + f().d"), "(+ a.b.c f().d)");
+	assert_eq!(expression_shape("(a + b).c"), "(+ a b).c");
+	assert_eq!(
+		instruction_shapes("p.age = 19\na.b.c = a.b.c + 1\np.age\nf().x"),
+		vec!["p.age = 19", "a.b.c = (+ a.b.c 1)", "p.age", "f().x"]
+	);
+}
+
+/// Tests that struct literals take named field initializers, which can be nested.
+#[test]
+fn test_struct_literal() {
+	assert_eq!(
+		expression_shape("new Person(name=n, age=18 + 1, birthday=new Date(year=2004))"),
+		"new Person(name=n, age=(+ 18 1), birthday=new Date(year=2004))"
+	);
+	assert_eq!(expression_shape("new Empty()"), "new Empty()");
+	assert_eq!(expression_shape("new Date(year=1).year"), "new Date(year=1).year");
+	assert_eq!(instruction_shapes("new Date(year=1)"), vec!["new Date(year=1)"]);
+
+	let (_, errors) = parse("def main() {\n\tvar p: Person = new Person(19)\n}");
+	assert!(matches!(
+		errors[0],
+		Error::ExpectedToken { found: Some(ref token), .. } if token.value == TokenKind::Int(19, None)
+	));
+}
+
+/// Tests that indexes bind like field accesses, take a single operand and can be assigned to.
+#[test]
+fn test_index() {
+	assert_eq!(expression_shape("-a@i * 2"), "(* (- a@i) 2)");
+	assert_eq!(expression_shape("a@(i + 1)"), "a@(+ i 1)");
+	assert_eq!(expression_shape("a@i.b@2"), "a@i.b@2");
+	assert_eq!(expression_shape("m@1@f()"), "m@1@f()");
+	assert_eq!(
+		instruction_shapes(
+			"a@3 = 42
+var m: arr<arr<int>(3)>(2)
+m@1@2 = a@3"
+		),
+		vec!["a@3 = 42", "var m", "m@1@2 = a@3"]
+	);
+
+	let (nodes, errors) = parse(
+		"struct Grid {
+	cells: arr<ptr Grid>(4)
+}",
+	);
+	assert_eq!(errors, Vec::new());
+	let Node::Struct(ref struct_) = nodes[0].node else { panic!("Expected struct, found {:?}", nodes[0]) };
+	assert_eq!(struct_.fields[0].data_type.value.to_string(), "arr<ptr Grid>(4)");
+
+	let (_, errors) = parse(
+		"def main() {
+	var a: arr<int>(n)
+}",
+	);
+	assert!(matches!(
+		errors[0],
+		Error::IllegalToken { token: Some(ref token), .. } if token.value == TokenKind::Identifier("n".to_owned())
+	));
+}
+
+/// Tests that `ref` and `deref` bind like unary operators and that dereferenced pointers can be assigned to.
+#[test]
+fn test_pointer_operations() {
+	assert_eq!(expression_shape("deref p.next + 1"), "(+ (deref p.next) 1)");
+	assert_eq!(expression_shape("ref a@1"), "(ref a@1)");
+	assert_eq!(expression_shape("deref deref p"), "(deref (deref p))");
+	assert_eq!(expression_shape("alloc ptr arr<int>(3)"), "alloc ptr arr<int>(3)");
+	assert_eq!(expression_shape("p == nil"), "(== p nil)");
+	assert_eq!(
+		instruction_shapes(
+			"deref p = 1
+(deref q)@2 = nil
+del p
+deref p"
+		),
+		vec!["(deref p) = 1", "(deref q)@2 = nil", "del p", "(deref p)"]
+	);
+}
+
+/// Tests that string literals are split into texts and interpolated names or field accesses, and that `{{` and `}}`
+/// are braces.
+#[test]
+fn test_string_literal() {
+	assert_eq!(expression_shape(r#""{name} is {age}!""#), r#"(str name " is " age "!")"#);
+	assert_eq!(expression_shape(r#""{{x}} = {x}""#), r#"(str "{x} = " x)"#);
+	assert_eq!(expression_shape(r#""{p.address.city}!""#), r#"(str p.address.city "!")"#);
+	assert_eq!(expression_shape(r#""""#), "(str)");
+	assert_eq!(expression_shape(r#""a" + "b".b"#), r#"(+ (str "a") (str "b").b)"#);
+
+	for source_code in [r#""{1x}""#, r#""{name""#, r#""}""#, r#""{}""#, r#""{a.}""#, r#""{.a}""#, r#""{a + b}""#] {
+		let (_, errors) = parse(&format!("def main() {{\n\tvar s: str = {}\n}}", source_code));
+		assert!(
+			matches!(errors[0], Error::IllegalToken { context: "string interpolation", .. }),
+			"Expected illegal interpolation in {}, found {:?}",
+			source_code,
+			errors
+		);
+	}
+}
+
+/// Tests that calls may span multiple lines.
+#[test]
+fn test_multi_line_call() {
+	assert_eq!(instruction_shapes("f(\n\t1,\n\tg(2,\n\t\t3)\n)\nh()"), vec!["f(1, g(2, 3))", "h()"]);
+	assert_eq!(instruction_shapes("f(1)\n(2)"), vec!["f(1)", "2"]);
+}
+
+/// Tests that two statements on the same line need to be separated by `;`.
+#[test]
+fn test_missing_statement_end() {
+	let (nodes, errors) = parse("def main() {\n\tx = 1 y = 2\n\tz = 3\n}");
+	assert_eq!(errors.len(), 1);
+	assert!(matches!(
+		errors[0],
+		Error::ExpectedToken { expected: TokenKind::EndOfLine, found: Some(ref token) }
+			if token.value == TokenKind::Identifier("y".to_owned())
+	));
+	// Parsing resumes on the next line
+	let Node::Function(ref main) = nodes[0].node else { panic!("Expected function, found {:?}", nodes[0]) };
+	assert_eq!(main.body.instructions.len(), 2);
+}
+
+/// Tests that comments in a block are attached to the instructions around them.
+#[test]
+fn test_comments_in_block() {
+	let (nodes, errors) = parse(
+		"def main() {
+			# leading
+			a = 1 # trailing
+			b = 2
+			# end
+		}
+		def empty() {
+			# nothing
+		}",
+	);
+	assert_eq!(errors, Vec::new());
+	let Node::Function(ref main) = nodes[0].node else { panic!("Expected function, found {:?}", nodes[0]) };
+	let comments: Vec<(Vec<String>, Vec<String>)> =
+		main.body.instructions.iter().map(|instruction| comment_texts(&instruction.comments)).collect();
+	assert_eq!(comments, vec![(vec!["leading".to_owned()], vec!["trailing".to_owned()]), (vec![], vec![])]);
+	assert_eq!(main.body.comments.iter().map(|comment| comment.value.as_str()).collect::<Vec<_>>(), vec!["end"]);
+
+	let Node::Function(ref empty) = nodes[1].node else { panic!("Expected function, found {:?}", nodes[1]) };
+	assert!(empty.body.instructions.is_empty());
+	assert_eq!(empty.body.comments.len(), 1);
+}
+
+/// Tests that comments in a struct body are attached to the fields.
+#[test]
+fn test_comments_in_struct() {
+	let (nodes, errors) = parse(
+		"struct Person {
+			# The name
+			name: int # trailing
+			age: int
+			# end
+		}",
+	);
+	assert_eq!(errors, Vec::new());
+	let Node::Struct(ref struct_) = nodes[0].node else { panic!("Expected struct, found {:?}", nodes[0]) };
+	let comments: Vec<(Vec<String>, Vec<String>)> =
+		struct_.fields.iter().map(|field| comment_texts(&field.comments)).collect();
+	assert_eq!(comments, vec![(vec!["The name".to_owned()], vec!["trailing".to_owned()]), (vec![], vec![])]);
+	// Comments after the last field are on their own lines
+	assert_eq!(struct_.fields[1].comments.following[0].value, "end");
+}
+
+/// Tests that comments in argument and parameter lists are attached to the arguments and parameters.
+#[test]
+fn test_comments_in_lists() {
+	let (nodes, errors) = parse(
+		"def main(
+			# leading
+			a: int, # trailing
+			b: int # last
+			# end
+		) {
+			f(1 # one
+			, 2, # two
+			# three
+			3)
+			g(# nothing
+			)
+		}",
+	);
+	assert_eq!(errors, Vec::new());
+	let Node::Function(ref main) = nodes[0].node else { panic!("Expected function, found {:?}", nodes[0]) };
+	let comments: Vec<(Vec<String>, Vec<String>)> =
+		main.prototype.args.iter().map(|arg| comment_texts(&arg.comments)).collect();
+	assert_eq!(
+		comments,
+		vec![
+			(vec!["leading".to_owned()], vec!["trailing".to_owned()]),
+			(vec![], vec!["last".to_owned(), "end".to_owned()])
+		]
+	);
+
+	let Instruction::Expression(Expression::FunctionCall(ref call)) = main.body.instructions[0].node else {
+		panic!("Expected function call, found {:?}", main.body.instructions[0])
+	};
+	let comments: Vec<(Vec<String>, Vec<String>)> =
+		call.params.iter().map(|param| comment_texts(&param.comments)).collect();
+	assert_eq!(
+		comments,
+		vec![(vec![], vec!["one".to_owned()]), (vec![], vec!["two".to_owned()]), (vec!["three".to_owned()], vec![])]
+	);
+}
+
+/// Tests that comments at the top level are attached to the nodes around them.
+#[test]
+fn test_comments_at_top_level() {
+	let (nodes, errors) = parse(
+		"# Leading
+		# comment
+		def main() {} # trailing
+
+		extern exit(status: int)
+		# end",
+	);
+	assert_eq!(errors, Vec::new());
+	assert_eq!(comment_texts(&nodes[0].comments), (vec!["Leading\n# comment".to_owned()], vec!["trailing".to_owned()]));
+	assert_eq!(comment_texts(&nodes[1].comments), (vec![], vec![]));
+	// Comments at the end of the file are on their own lines
+	assert_eq!(nodes[1].comments.following[0].value, "end");
+}
+
+/// Tests that doc comments are attached to the functions, structs and fields after them.
+#[test]
+fn test_doc_comments() {
+	let (nodes, errors) = parse(
+		"## A person
+		struct Person {
+			## The age
+			# in years
+			age: int
+			name: int
+		}
+		## Unused
+		# comment
+		## Exits
+		extern exit(status: int)
+		def main() {
+			## Not documenting anything
+			exit(0)
+		}",
+	);
+	assert_eq!(errors, Vec::new());
+	let doc = |doc: &Option<crate::ast::Comment>| doc.as_ref().map(|doc| doc.value.clone());
+
+	let Node::Struct(ref struct_) = nodes[0].node else { panic!("Expected struct, found {:?}", nodes[0]) };
+	assert_eq!(doc(&struct_.doc), Some("A person".to_owned()));
+	assert_eq!(doc(&struct_.fields[0].doc), Some("The age".to_owned()));
+	assert_eq!(comment_texts(&struct_.fields[0].comments), (vec!["in years".to_owned()], vec![]));
+	assert_eq!(doc(&struct_.fields[1].doc), None);
+
+	// A doc comment followed by another one becomes a normal comment
+	let Node::FunctionPrototype(ref exit) = nodes[1].node else { panic!("Expected prototype, found {:?}", nodes[1]) };
+	assert_eq!(doc(&exit.doc), Some("Exits".to_owned()));
+	assert_eq!(comment_texts(&nodes[1].comments), (vec!["Unused".to_owned(), "comment".to_owned()], vec![]));
+
+	let Node::Function(ref main) = nodes[2].node else { panic!("Expected function, found {:?}", nodes[2]) };
+	assert_eq!(doc(&main.prototype.doc), None);
+	assert_eq!(
+		comment_texts(&main.body.instructions[0].comments),
+		(vec!["Not documenting anything".to_owned()], vec![])
+	);
+}
+
+/// Tests that reserved keywords cannot be used as names of variables, functions, structs and fields.
+#[test]
+fn test_reserved_keyword_as_name() {
+	let (_, errors) = parse(
+		"def default() {}
+		def main(print: int) {}
+		def other() {
+			var debug: int = 1
+		}
+		struct enum {}
+		struct Person {
+			const: int
+		}",
+	);
+	let names: Vec<(&str, usize, usize)> = errors
+		.iter()
+		.map(|err| match err {
+			Error::ReservedKeyword(name) => {
+				(name.value.as_str(), name.position.position().start.line, name.position.position().start.column)
+			},
+			other => panic!("Unexpected error {:?}", other),
+		})
+		.collect();
+	assert_eq!(names, vec![("default", 1, 5), ("print", 2, 12), ("debug", 4, 8), ("enum", 6, 10), ("const", 8, 4)]);
+}
+
+/// Returns the texts of the leading and trailing comments.
+fn comment_texts(comments: &Comments) -> (Vec<String>, Vec<String>) {
+	let texts = |comments: &[crate::ast::Comment]| comments.iter().map(|comment| comment.value.clone()).collect();
+	(texts(&comments.leading), texts(&comments.trailing))
+}
+
+/// Parses `body` as body of a function and returns the shapes of its instructions, see [`shape`].
+fn instruction_shapes(body: &str) -> Vec<String> {
+	let (nodes, errors) = parse(&format!("def main() {{\n{}\n}}", body));
+	assert_eq!(errors, Vec::new());
+	let Node::Function(ref function) = nodes[0].node else { panic!("Expected function, found {:?}", nodes[0]) };
+	function
+		.body
+		.instructions
+		.iter()
+		.map(|instruction| match &instruction.node {
+			Instruction::Expression(expression) => shape(expression),
+			Instruction::Statement(Statement::VariableAssignment(assignment)) => {
+				format!("{} = {}", shape(&assignment.target), shape(&assignment.value))
+			},
+			Instruction::Statement(Statement::VariableDeclaration(declaration)) => match &declaration.value {
+				Some(value) => format!("var {} = {}", *declaration.name, shape(value)),
+				None => format!("var {}", *declaration.name),
+			},
+			Instruction::IfElse(if_else) => {
+				let else_ifs: Vec<String> = if_else.else_ifs.iter().map(|else_if| shape(&else_if.condition)).collect();
+				format!("if {} else if [{}]", shape(&if_else.condition), else_ifs.join(", "))
+			},
+			Instruction::Statement(Statement::Delete(pointer)) => format!("del {}", shape(pointer)),
+			Instruction::Statement(Statement::Return(return_)) => match &return_.value {
+				Some(value) => format!("return {}", shape(value)),
+				None => "return".to_owned(),
+			},
+			Instruction::Statement(Statement::Break(jump)) => jump_shape("break", jump),
+			Instruction::Statement(Statement::Continue(jump)) => jump_shape("continue", jump),
+			Instruction::WhileLoop(while_loop) => {
+				format!("{}while {}", label_shape(&while_loop.label), shape(&while_loop.condition))
+			},
+			Instruction::ForLoop(for_loop) => {
+				label_shape(&for_loop.label)
+					+ &match &for_loop.iterable {
+						Iterable::Range(range) => {
+							let operator = if range.inclusive { "..=" } else { ".." };
+							let step =
+								range.step.as_ref().map(|step| format!(" step {}", shape(step))).unwrap_or_default();
+							format!(
+								"for {} in {}{}{}{}",
+								*for_loop.variable,
+								shape(&range.start),
+								operator,
+								shape(&range.end),
+								step
+							)
+						},
+						Iterable::Array(array) => format!("for {} of {}", *for_loop.variable, shape(array)),
+					}
+			},
+		})
+		.collect()
+}
+
+/// Renders a `break` or `continue` with its optional label.
+fn jump_shape(keyword: &str, jump: &Jump) -> String {
+	match &jump.label {
+		Some(label) => format!("{} {}", keyword, label.value),
+		None => keyword.to_owned(),
+	}
+}
+
+/// Renders the optional label of a loop like `outer: `.
+fn label_shape(label: &Option<PositionContainer<String>>) -> String {
+	label.as_ref().map(|label| format!("{}: ", label.value)).unwrap_or_default()
+}
+
+/// Parses `expression` as initializer of a variable and returns its shape, see [`shape`].
+fn expression_shape(expression: &str) -> String {
+	let (nodes, errors) = parse(&format!("def main() {{ var x: int = {} }}", expression));
+	assert_eq!(errors, Vec::new());
+	let Node::Function(ref function) = nodes[0].node else { panic!("Expected function, found {:?}", nodes[0]) };
+	let Instruction::Statement(Statement::VariableDeclaration(ref declaration)) = function.body.instructions[0].node
+	else {
+		panic!("Expected variable declaration, found {:?}", function.body.instructions[0].node)
+	};
+	shape(declaration.value.as_ref().unwrap())
+}
+
+/// Renders the structure of an expression as an s-expression like `(+ a (* b c))`, leaving out positions.
+fn shape(expression: &Expression) -> String {
+	match expression {
+		Expression::BinaryExpression(binary) => {
+			format!("({} {} {})", *binary.operator, shape(&binary.lhs), shape(&binary.rhs))
+		},
+		Expression::Unary(unary) => format!("({} {})", *unary.operator, shape(&unary.operand)),
+		Expression::FunctionCall(call) => {
+			let params: Vec<String> = call.params.iter().map(|param| shape(param)).collect();
+			format!("{}({})", *call.name, params.join(", "))
+		},
+		Expression::FieldAccess(field_access) => format!("{}.{}", shape(&field_access.base), *field_access.field),
+		Expression::Index(index) => format!("{}@{}", shape(&index.base), shape(&index.index)),
+		Expression::StructLiteral(struct_literal) => {
+			let fields: Vec<String> =
+				struct_literal.fields.iter().map(|field| format!("{}={}", *field.name, shape(&field.value))).collect();
+			format!("new {}({})", *struct_literal.name, fields.join(", "))
+		},
+		Expression::Alloc(alloc) => format!("alloc {}", alloc.data_type.value),
+		Expression::String(string_literal) => {
+			let parts = string_literal.parts.iter().map(|part| match part {
+				StringPart::Text(text) => format!(" {:?}", text),
+				StringPart::Interpolation(value) => format!(" {}", shape(value)),
+			});
+			format!("(str{})", parts.collect::<String>())
+		},
+		Expression::Number(number) => match **number {
+			NumberKind::Int(int, _) => int.to_string(),
+			NumberKind::Float(float, _) => float.to_string(),
+		},
+		Expression::Variable(variable) => variable.value.clone(),
+		Expression::Nil(_) => "nil".to_owned(),
+	}
+}
+
+/// Boilerplate code for converting source code into an AST using a lexer and parser.
+fn parse(source_code: &str) -> (Vec<Commented<Node>>, Vec<Error>) {
+	let source = Arc::new(Source::new("testfile".to_owned(), source_code.to_owned()));
+	let (tokens, lexer_errors) = Lexer::new(&source).tokenize();
+	assert_eq!(lexer_errors, Vec::new());
+	Parser::new(tokens.into_iter(), source.clone()).parse()
+}
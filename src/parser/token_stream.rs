@@ -0,0 +1,85 @@
+use std::{iter::Peekable, mem, sync::Arc};
+
+use crate::{
+	ast::Comment,
+	source::{Source, SourcePositionRange, Span},
+	token::{LocatedToken, Token},
+};
+
+/// The [`Token`]s consumed by the [`Parser`](super::Parser), together with their [`Source`].
+///
+/// Tokens only carry their compact [`Span`]. They are located in the source only when they become part of the AST
+/// or of an [`Error`](super::Error).
+pub(crate) struct TokenStream<I>
+where
+	I: Iterator<Item = Token>,
+{
+	tokens: Peekable<I>,
+	source: Arc<Source>,
+	/// Comments in front of an expression, which are kept until the enclosing instruction or top level node takes
+	/// them as trailing comments.
+	comments: Vec<Comment>,
+}
+
+impl<I> TokenStream<I>
+where
+	I: Iterator<Item = Token>,
+{
+	pub(crate) fn new(tokens: I, source: Arc<Source>) -> Self {
+		Self { tokens: tokens.peekable(), source, comments: Vec::new() }
+	}
+
+	/// Returns the next token without consuming it.
+	pub(crate) fn peek(&mut self) -> Option<&Token> {
+		self.tokens.peek()
+	}
+
+	/// Returns the next token located in the source code without consuming it, e.g. to report it in an
+	/// [`Error`](super::Error).
+	pub(crate) fn peek_located(&mut self) -> Option<LocatedToken> {
+		let token = self.tokens.peek().cloned();
+		self.locate(token)
+	}
+
+	/// Consumes the next token and locates it in the source code, e.g. to report it in an [`Error`](super::Error).
+	pub(crate) fn next_located(&mut self) -> Option<LocatedToken> {
+		let token = self.tokens.next();
+		self.locate(token)
+	}
+
+	/// Consumes and returns the next token if `func` returns `true` for it.
+	pub(crate) fn next_if(&mut self, func: impl FnOnce(&Token) -> bool) -> Option<Token> {
+		self.tokens.next_if(func)
+	}
+
+	/// Returns the position of `span` in the source code.
+	pub(crate) fn position_of(&self, span: Span) -> SourcePositionRange {
+		self.source.locate(span)
+	}
+
+	/// Keeps `comments` that have no node to be attached to until they are [taken](Self::take_comments).
+	pub(crate) fn keep_comments(&mut self, comments: Vec<Comment>) {
+		self.comments.extend(comments);
+	}
+
+	/// Takes the comments [kept](Self::keep_comments) since the last call.
+	pub(crate) fn take_comments(&mut self) -> Vec<Comment> {
+		mem::take(&mut self.comments)
+	}
+
+	/// Locates `token` in the source code, e.g. to report it in an [`Error`](super::Error).
+	pub(crate) fn locate(&self, token: Option<Token>) -> Option<LocatedToken> {
+		token.map(|token| token.locate(&self.source))
+	}
+}
+
+impl<I> Iterator for TokenStream<I>
+where
+	I: Iterator<Item = Token>,
+{
+	type Item = Token;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.tokens.next()
+	}
+}
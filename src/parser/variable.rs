@@ -1,6 +1,4 @@
-use std::iter::Peekable;
-
-use super::Result;
+use super::{Result, TokenStream};
 use crate::{
 	ast,
 	parser::{expression, helper, variable, Error},
@@ -9,41 +7,68 @@ use crate::{
 };
 
 pub fn parse_variable_declaration(
-	tokens: &mut Peekable<impl Iterator<Item = Token>>,
+	tokens: &mut TokenStream<impl Iterator<Item = Token>>,
 ) -> Result<ast::statement::VariableDeclaration> {
-	helper::parse_variable_declaration(tokens.next())?;
-	let name = helper::parse_identifier(tokens.next())?;
-	helper::parse_colon(tokens.next())?;
+	helper::parse_variable_declaration(tokens)?;
+	let name = helper::parse_name(tokens)?;
+	helper::parse_colon(tokens)?;
 	let data_type = variable::parse_data_type(tokens)?;
-	helper::parse_equal(tokens.next())?;
-	let value = expression::parse_primary_expression(tokens)?;
+	let value = match tokens.next_if(|token| token.value == TokenKind::Equal) {
+		Some(_) => Some(expression::parse_binary_expression(tokens)?),
+		None => None,
+	};
 	Ok(ast::statement::VariableDeclaration { name, data_type, value })
 }
 
 pub(crate) fn parse_data_type(
-	tokens: &mut Peekable<impl Iterator<Item = Token>>,
+	tokens: &mut TokenStream<impl Iterator<Item = Token>>,
 ) -> Result<PositionContainer<ast::statement::DataType>> {
 	match tokens.next() {
 		// Pointer type
-		Some(Token { value: TokenKind::Pointer, position }) => {
+		Some(Token { value: TokenKind::Pointer, span }) => {
 			// Recursively call parse_data_type to parse the type the pointer points to. This recursive calling
 			// allows types like `ptr ptr int` to be parsed.
 			let type_to_point_to = parse_data_type(tokens)?;
-			Ok(PositionContainer { value: ast::statement::DataType::Pointer(Box::new(type_to_point_to)), position })
+			Ok(PositionContainer {
+				value: ast::statement::DataType::Pointer(Box::new(type_to_point_to)),
+				position: tokens.position_of(span),
+			})
+		},
+		// Array type like `arr<int>(10)`
+		Some(Token { value: TokenKind::Array, span }) => {
+			helper::parse_less(tokens)?;
+			let element = parse_data_type(tokens)?;
+			helper::parse_greater(tokens)?;
+			helper::parse_opening_parenthesis(tokens)?;
+			let length = match tokens.next() {
+				Some(Token { value: TokenKind::Int(length, None), .. }) if length > 0 => length,
+				other => return Err(Error::IllegalToken { token: tokens.locate(other), context: "array length" }),
+			};
+			helper::parse_closing_parenthesis(tokens)?;
+			Ok(PositionContainer {
+				value: ast::statement::DataType::Array { element: Box::new(element), length },
+				position: tokens.position_of(span),
+			})
 		},
 		// Normal type
-		Some(Token { value: TokenKind::Identifier(type_str), position }) => {
+		Some(Token { value: TokenKind::Identifier(type_str), span }) => {
 			match ast::statement::BasicDataType::try_from(type_str.as_str()) {
 				// Basic data type
-				Ok(basic_data_type) => {
-					Ok(PositionContainer { value: ast::statement::DataType::Basic(basic_data_type), position })
-				},
+				Ok(basic_data_type) => Ok(PositionContainer {
+					value: ast::statement::DataType::Basic(basic_data_type),
+					position: tokens.position_of(span),
+				}),
 				Err(_) => {
 					// User-defined data type (struct)
-					Ok(PositionContainer { value: ast::statement::DataType::Struct(type_str), position })
+					Ok(PositionContainer {
+						value: ast::statement::DataType::Struct(type_str),
+						position: tokens.position_of(span),
+					})
 				},
 			}
 		},
-		other => Err(Error::ExpectedToken { expected: TokenKind::Identifier(String::new()), found: other }),
+		other => {
+			Err(Error::ExpectedToken { expected: TokenKind::Identifier(String::new()), found: tokens.locate(other) })
+		},
 	}
 }
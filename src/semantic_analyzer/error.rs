@@ -17,6 +17,51 @@ pub enum Error {
 	#[error("{}: TypeMismatch: expected {}, got {}", position, expected, actual)]
 	TypeMismatch { expected: DataType, position: SourcePositionRange, actual: DataType },
 
+	#[error("{}: IllegalOperandType: Operator `{operator}` cannot be applied to `{actual}`.", position)]
+	IllegalOperandType { operator: String, actual: DataType, position: SourcePositionRange },
+
+	#[error("{}: NotAddressable: Only variables, fields, array elements and dereferenced pointers have an address to be referenced or assigned to.", position)]
+	NotAddressable { position: SourcePositionRange },
+
+	#[error("{}: NilNotPointer: `nil` is no value of the non-pointer type `{expected}`.", position)]
+	NilNotPointer { expected: DataType, position: SourcePositionRange },
+
+	#[error("{}: UntypedNil: The pointer type of `nil` cannot be inferred here.", position)]
+	UntypedNil { position: SourcePositionRange },
+
+	#[error("{}: NotInterpolatable: Cannot interpolate `{actual}` into a string, expected str, int, float, bool or char.", position)]
+	NotInterpolatable { actual: DataType, position: SourcePositionRange },
+
+	#[error("{}: LiteralOutOfRange: Number literal is out of the range of `{data_type}`.", position)]
+	LiteralOutOfRange { data_type: DataType, position: SourcePositionRange },
+
+	#[error("{}: NoValues: Type `{data_type}` has no values, it is only allowed as return type or behind `ptr`.", position)]
+	NoValues { data_type: DataType, position: SourcePositionRange },
+
+	#[error("{}: NotIterable: Cannot iterate over `{actual}`, expected an array.", position)]
+	NotIterable { actual: DataType, position: SourcePositionRange },
+
+	#[error("{}: JumpOutsideLoop: `{keyword}` is only allowed inside a loop.", position)]
+	JumpOutsideLoop { keyword: &'static str, position: SourcePositionRange },
+
+	#[error("{}: UnknownLabel: No enclosing loop is labeled `{}`.", label.position, label.value)]
+	UnknownLabel { label: PositionContainer<String> },
+
+	#[error("{}: UnknownField: Struct `{struct_name}` has no field `{}`.", field.position, field.value)]
+	UnknownField { struct_name: String, field: PositionContainer<String> },
+
+	#[error("{}: MissingField: Field `{field}` of struct `{struct_name}` is not initialized.", position)]
+	MissingField { struct_name: String, field: String, position: SourcePositionRange },
+
+	#[error("{}: DuplicateField: Field `{}` is initialized more than once.", field.position, field.value)]
+	DuplicateField { field: PositionContainer<String> },
+
+	#[error("{}: UndefinedStruct: Type `{name}` is used, but no such struct is defined.", position)]
+	UndefinedStruct { name: String, position: SourcePositionRange },
+
+	#[error("{}: IndexOutOfBounds: Index {index} is out of bounds for an array of length {length}.", position)]
+	IndexOutOfBounds { index: u64, length: u64, position: SourcePositionRange },
+
 	#[error("{}: UndefinedFunctionCall: Call of function `{}(...)`, but no such function is defined.", function_call.name.position, function_call.name.deref())]
 	UndefinedFunctionCall { function_call: FunctionCall },
 
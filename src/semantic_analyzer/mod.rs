@@ -2,6 +2,8 @@
 
 mod error;
 mod symbol_table;
+#[cfg(test)]
+mod test;
 mod type_check;
 mod variable;
 
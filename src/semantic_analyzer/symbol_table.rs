@@ -31,7 +31,6 @@ impl SymbolTable {
 			ast::Node::Function(function) => self.function(&function.prototype),
 			ast::Node::Struct(struct_) => self.struct_(struct_),
 			ast::Node::FunctionPrototype(function_prototype) => self.function(function_prototype),
-			_ => todo!(),
 		}
 	}
 
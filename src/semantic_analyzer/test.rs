@@ -0,0 +1,507 @@
+use std::sync::Arc;
+
+use crate::{
+	ast::statement::{BasicDataType, DataType},
+	lexer::Lexer,
+	parser::Parser,
+	semantic_analyzer::{Error, SymbolTable, TypeChecker},
+	source::Source,
+};
+
+/// Tests that assigning to an outer variable inside an if keeps the variable declared after the if.
+#[test]
+fn test_assignment_in_if_keeps_outer_variable() {
+	let result = type_check(
+		"def main(x: int): int {
+			var code: int = 0
+			if x < 1 {
+				code = 1
+			} else if x < 2 {
+				code = 2
+			} else {
+				code = 3
+			}
+			return code
+		}",
+	);
+	assert_eq!(result, Ok(()));
+}
+
+/// Tests that each branch of an if-else chain has its own scope.
+#[test]
+fn test_if_else_branch_scopes() {
+	// The same name can be declared in each branch
+	let result = type_check(
+		"def main(x: int): int {
+			if x < 1 {
+				var y: int = 1
+			} else if x < 2 {
+				var y: int = 2
+			} else {
+				var y: int = 3
+			}
+			return x
+		}",
+	);
+	assert_eq!(result, Ok(()));
+
+	// Variables of a branch are not visible after the if-else
+	let result = type_check(
+		"def main(x: int): int {
+			if x < 1 {
+			} else if x < 2 {
+				var y: int = 2
+			}
+			return y
+		}",
+	);
+	assert!(matches!(result, Err(Error::UndeclaredVariable { ref name }) if name.value == "y"));
+
+	// Variables of a branch are not visible in the conditions of the following branches
+	let result = type_check(
+		"def main(x: int): int {
+			if x < 1 {
+				var y: int = 1
+			} else if y < 2 {
+			}
+			return x
+		}",
+	);
+	assert!(matches!(result, Err(Error::UndeclaredVariable { ref name }) if name.value == "y"));
+}
+
+/// Tests that the conditions of `if`, `else if` and `while` are `bool`s and that variables used as condition or
+/// instruction are declared.
+#[test]
+fn test_conditions_and_variables() {
+	let check = |body: &str| type_check(&format!("def main(x: int, done: bool) {{\n{}\n}}", body));
+	assert_eq!(check("if done {\n} else if x < 1 {\n}\nwhile not done {\n}\nx"), Ok(()));
+	assert!(matches!(check("if 1.5 {\n}"), Err(Error::TypeMismatch { .. })));
+	assert!(matches!(check("if done {\n} else if x {\n}"), Err(Error::TypeMismatch { .. })));
+	assert!(matches!(check("while x + 1 {\n}"), Err(Error::TypeMismatch { .. })));
+	assert!(matches!(check("if missing {\n}"), Err(Error::UndeclaredVariable { ref name }) if name.value == "missing"));
+	assert!(matches!(check("missing"), Err(Error::UndeclaredVariable { ref name }) if name.value == "missing"));
+}
+
+/// Tests that the loop variable of a for loop has the type of the range and is only visible in the loop body.
+#[test]
+fn test_for_loop_variable() {
+	let result = type_check(
+		"def main(n: int): int {
+			var sum: int = 0
+			for i in 0..n step 2 {
+				sum = sum + i
+			}
+			for i in n..=0 step -1 {
+				sum = sum + i
+			}
+			return sum
+		}",
+	);
+	assert_eq!(result, Ok(()));
+
+	// The loop variable is not visible after the loop
+	let result = type_check("def main(n: int): int {\n\tfor i in 0..n {\n\t}\n\treturn i\n}");
+	assert!(matches!(result, Err(Error::UndeclaredVariable { ref name }) if name.value == "i"));
+
+	// The loop variable must not shadow another variable
+	let result = type_check("def main(n: int): int {\n\tfor n in 0..10 {\n\t}\n\treturn n\n}");
+	assert!(matches!(result, Err(Error::Redeclaration { .. })));
+}
+
+/// Tests that ranges consist of integers of the same type.
+#[test]
+fn test_range_types() {
+	let result = type_check("def main(x: float): float {\n\tfor i in x..2.0 {\n\t}\n\treturn x\n}");
+	assert!(matches!(result, Err(Error::IllegalOperandType { ref operator, .. }) if operator == ".."));
+
+	let result = type_check("def main(x: float): float {\n\tfor i in 0..10 step x {\n\t}\n\treturn x\n}");
+	assert!(matches!(result, Err(Error::TypeMismatch { .. })));
+}
+
+/// Tests that `break` and `continue` are only allowed inside loops and refer to labels of enclosing loops.
+#[test]
+fn test_break_continue() {
+	let result = type_check(
+		"def main(n: int): int {
+			outer: while n > 0 {
+				for i in 0..n {
+					if i == 2 {
+						continue outer
+					}
+					break
+				}
+				continue
+			}
+			return n
+		}",
+	);
+	assert_eq!(result, Ok(()));
+
+	let result = type_check("def main(n: int): int {\n\tif n > 0 {\n\t\tbreak\n\t}\n\treturn n\n}");
+	assert!(matches!(result, Err(Error::JumpOutsideLoop { keyword: "break", .. })));
+
+	// The label of a finished loop is not visible anymore
+	let result = type_check(
+		"def main(n: int): int {
+			first: while n > 0 {
+			}
+			while n > 0 {
+				continue first
+			}
+			return n
+		}",
+	);
+	assert!(matches!(result, Err(Error::UnknownLabel { ref label }) if label.value == "first"));
+}
+
+/// Tests that the types of fields are resolved, also through pointers.
+#[test]
+fn test_field_access() {
+	let structs = "struct Date {\n\tyear: int\n}\nstruct Person {\n\tbirthday: Date\n\tparent: ptr Person\n}\n";
+	let result = type_check(&format!(
+		"{}def main(p: Person): int {{
+			p.parent.birthday.year = p.birthday.year + 1
+			return p.parent.parent.birthday.year
+		}}",
+		structs
+	));
+	assert_eq!(result, Ok(()));
+
+	let result = type_check(&format!("{}def main(p: Person): int {{\n\treturn p.birthday.month\n}}", structs));
+	assert!(matches!(
+		result,
+		Err(Error::UnknownField { ref struct_name, ref field }) if struct_name == "Date" && field.value == "month"
+	));
+
+	let result = type_check(&format!("{}def main(p: Person): int {{\n\tp.birthday = 1\n\treturn 0\n}}", structs));
+	assert!(matches!(result, Err(Error::TypeMismatch { .. })));
+
+	let result = type_check("def main(x: int): int {\n\treturn x.y\n}");
+	assert!(matches!(result, Err(Error::IllegalOperandType { ref operator, .. }) if operator == "."));
+}
+
+/// Tests that struct literals initialize each field exactly once with a value of the right type.
+#[test]
+fn test_struct_literal() {
+	let struct_ = "struct Person {\n\tage: int\n\tsize: float\n}\n";
+	let check = |literal: &str| {
+		type_check(&format!("{}def main(): int {{\n\tvar p: Person = {}\n\treturn p.age\n}}", struct_, literal))
+	};
+
+	assert_eq!(check("new Person(size=1.8, age=19)"), Ok(()));
+	assert!(matches!(
+		check("new Person(age=19)"),
+		Err(Error::MissingField { ref struct_name, ref field, .. }) if struct_name == "Person" && field == "size"
+	));
+	assert!(matches!(
+		check("new Person(age=19, size=1.8, name=1)"),
+		Err(Error::UnknownField { ref field, .. }) if field.value == "name"
+	));
+	assert!(matches!(
+		check("new Person(age=19, size=1.8, age=20)"),
+		Err(Error::DuplicateField { ref field }) if field.value == "age"
+	));
+	assert!(matches!(check("new Person(age=19, size=2)"), Err(Error::TypeMismatch { .. })));
+	assert!(matches!(
+		check("new Human(age=19)"),
+		Err(Error::UndefinedStruct { ref name, .. }) if name == "Human"
+	));
+	// Array fields may be left out, since there are no array literals
+	assert_eq!(
+		type_check(
+			"struct Bag {\n\titems: arr<int>(3)\n\tcount: int\n}\ndef main() {\n\tvar b: Bag = new Bag(count=1)\n}"
+		),
+		Ok(())
+	);
+}
+
+/// Tests that only arrays can be indexed, with integers within their bounds, and that only elements can be assigned.
+#[test]
+fn test_index() {
+	let result = type_check(
+		"struct Grid {
+			cells: arr<arr<float>(3)>(2)
+		}
+		def main(g: Grid, i: int): float {
+			var sum: float = 0.0
+			for row of g.cells {
+				for cell of row {
+					sum = sum + cell
+				}
+			}
+			g.cells@i@2 = sum
+			return g.cells@1@i
+		}",
+	);
+	assert_eq!(result, Ok(()));
+
+	let check = |instruction: &str| {
+		type_check(&format!(
+			"def main(x: float): int {{
+	var a: arr<int>(4)
+	{}
+	return 0
+}}",
+			instruction
+		))
+	};
+	assert!(matches!(check("a@4 = 1"), Err(Error::IndexOutOfBounds { index: 4, length: 4, .. })));
+	assert!(matches!(check("a@x = 1"), Err(Error::IllegalOperandType { ref operator, .. }) if operator == "@"));
+	assert!(matches!(check("x@1 = 1.0"), Err(Error::IllegalOperandType { ref operator, .. }) if operator == "@"));
+	assert!(matches!(check("a@1 = 1.0"), Err(Error::TypeMismatch { .. })));
+	assert!(
+		matches!(check("var b: arr<int>(4) = a"), Err(Error::IllegalOperandType { ref operator, .. }) if operator == "=")
+	);
+	assert!(matches!(check("for y of x {\n}"), Err(Error::NotIterable { .. })));
+
+	let result = type_check(
+		"struct Person {
+			age: int
+		}
+		def main(people: arr<Person>(3), grid: arr<arr<Person>(2)>(3), i: int): int {
+			grid@i@1.age = people@0.age
+			return grid@0@i.age
+		}",
+	);
+	assert_eq!(result, Ok(()));
+}
+
+/// Tests that pointer operations produce the exact pointer types and that `nil` is only used as a pointer.
+#[test]
+fn test_pointer_operations() {
+	let result = type_check(
+		"struct Node {
+			value: int
+			next: ptr Node
+		}
+		def push(head: ptr Node, value: int): ptr Node {
+			if value < 0 {
+				return nil
+			}
+			var node: ptr Node = alloc Node
+			deref node = new Node(value=value, next=head)
+			return node
+		}
+		def main(): int {
+			var x: int = 1
+			var p: ptr ptr int = alloc ptr int
+			deref p = ref x
+			deref deref p = 2
+			var head: ptr Node = push(nil, x)
+			if nil =/= head.next and head == nil {
+			}
+			del p
+			return (deref head).value + x
+		}",
+	);
+	assert_eq!(result, Ok(()));
+
+	let check = |instruction: &str| {
+		type_check(&format!(
+			"def main(x: int): int {{
+	var p: ptr int = ref x
+	{}
+	return 0
+}}",
+			instruction
+		))
+	};
+	assert!(matches!(check("var q: ptr float = ref x"), Err(Error::TypeMismatch { .. })));
+	assert!(matches!(check("deref x = 1"), Err(Error::IllegalOperandType { ref operator, .. }) if operator == "deref"));
+	assert!(matches!(check("del x"), Err(Error::IllegalOperandType { ref operator, .. }) if operator == "del"));
+	assert!(matches!(check("var q: ptr int = ref 1"), Err(Error::NotAddressable { .. })));
+	assert!(matches!(check("-x = 2"), Err(Error::NotAddressable { .. })));
+	assert!(matches!(check("x = nil"), Err(Error::NilNotPointer { .. })));
+	assert!(matches!(check("var b: bool = nil == nil"), Err(Error::UntypedNil { .. })));
+	assert!(matches!(check("return nil"), Err(Error::NilNotPointer { .. })));
+	assert!(matches!(check("return 1.0"), Err(Error::TypeMismatch { .. })));
+}
+
+/// Tests that strings can be concatenated, compared and interpolated, and passed to C functions.
+#[test]
+fn test_string() {
+	let result = type_check(
+		r#"extern write(fd: int, buffer: ptr char, length: int): int
+		def main(name: str, age: int, size: float, adult: bool): int {
+			var text: str = "{name} is {age} years old, {size} m tall and adult: {adult}" + "!"
+			if text == "" or "a" < text {
+				return 1
+			}
+			return write(1, text._cstr, text._len)
+		}"#,
+	);
+	assert_eq!(result, Ok(()));
+
+	let check = |expression: &str| {
+		type_check(&format!(
+			"struct Person {{\n\tage: int\n}}\ndef main(p: Person, text: str): str {{\n\treturn {}\n}}",
+			expression
+		))
+	};
+	assert!(matches!(check(r#"text - "b""#), Err(Error::IllegalOperandType { ref operator, .. }) if operator == "-"));
+	assert!(matches!(check(r#"text + 1"#), Err(Error::TypeMismatch { .. })));
+	assert!(matches!(check(r#""{p}""#), Err(Error::NotInterpolatable { actual: DataType::Struct(_), .. })));
+	assert_eq!(check(r#""{p.age}""#), Ok(()));
+	// Interpolated names are located on their own, also after escape sequences
+	let Err(Error::UndeclaredVariable { name }) = check(r#""\u{1F600}\t{missing}""#) else {
+		panic!("Expected undeclared variable")
+	};
+	assert_eq!(name.value, "missing");
+	assert_eq!(&name.position.source.text[name.position.span.range()], "missing");
+	assert!(matches!(
+		check("text._size"),
+		Err(Error::UnknownField { ref struct_name, .. }) if struct_name == "str"
+	));
+}
+
+/// Tests the sized number types, number literals taking the expected type, functions returning `nothing` and
+/// `ptr any`.
+#[test]
+fn test_primitive_types() {
+	let result = type_check(
+		r#"extern malloc(size: uint64): ptr any
+		extern free(memory: ptr any)
+		def log(text: str) {
+		}
+		def main(small: int8, large: uint64, ratio: float64): int32 {
+			var byte: uint8 = 255u8 - 1
+			var negative: int16 = -3
+			var exact: float32 = 1.5f32 * 2.0
+			var memory: ptr int64 = malloc(8u64 * large)
+			var bigger: bool = 0 < large and ratio > -0.5 and small =/= 1i8
+			for i in 0..byte {
+				log("{i} {small} {large} {ratio} {exact}")
+			}
+			free(memory)
+			log("done")
+			return 0
+		}"#,
+	);
+	assert_eq!(result, Ok(()));
+
+	let check = |instruction: &str| {
+		type_check(&format!(
+			"def nop() {{
+}}
+def main(x: uint8, p: ptr any): int {{
+	{}
+	return 0
+}}",
+			instruction
+		))
+	};
+	assert_eq!(
+		check(
+			"var y: uint16 = 7u16
+	var b: bool = x + 1 == 2 * x"
+		),
+		Ok(())
+	);
+	assert_eq!(
+		check(
+			"var q: ptr float32 = p
+	p = q
+	nop()"
+		),
+		Ok(())
+	);
+	assert!(matches!(check("var y: int8 = x"), Err(Error::TypeMismatch { .. })));
+	assert!(matches!(check("var y: uint8 = 1.0"), Err(Error::TypeMismatch { .. })));
+	assert!(matches!(check("var y: float = 1"), Err(Error::TypeMismatch { .. })));
+	assert!(matches!(check("var y: int = 1u32"), Err(Error::TypeMismatch { .. })));
+	assert!(matches!(check("var y: bool = x < 1i8"), Err(Error::TypeMismatch { .. })));
+	assert!(matches!(
+		check("return nop()"),
+		Err(Error::TypeMismatch { actual: DataType::Basic(BasicDataType::Nothing), .. })
+	));
+	let illegal_operand = |instruction: &str, expected_operator: &str| {
+		let result = type_check(&format!(
+			"struct Point {{
+	x: int
+}}
+def main(b: bool, p: ptr int, s: Point, a: arr<int>(2), text: str): int {{
+	{}
+	return 0
+}}",
+			instruction
+		));
+		assert!(
+			matches!(result, Err(Error::IllegalOperandType { ref operator, .. }) if operator == expected_operator),
+			"{} returned {:?}",
+			instruction,
+			result
+		);
+	};
+	illegal_operand("var c: bool = b + b", "+");
+	illegal_operand("var q: ptr int = p - p", "-");
+	illegal_operand("var t: Point = s * s", "*");
+	illegal_operand("a / a", "/");
+	illegal_operand("var t: str = text - text", "-");
+	illegal_operand("var c: bool = s == s", "==");
+	illegal_operand("var c: bool = a =/= a", "=/=");
+	illegal_operand("var c: bool = b < b", "<");
+	illegal_operand("var c: bool = p >= p", ">=");
+	assert_eq!(check("var c: bool = p == nil and p =/= p and \"a\" < \"b\""), Ok(()));
+	assert_eq!(
+		check(
+			"var min: int8 = -128
+	var max: uint64 = 18446744073709551615u64
+	var large: int64 = 5000000000
+	var small: float32 = -3.4e38
+	var min8: int8 = -128i8
+	var min64: int64 = -9223372036854775808
+	var typed_min64: int64 = -9223372036854775808i64
+	var sum8: int8 = 1 + 1
+	var nested8: int8 = -1 + 2 * 3
+	var sum64: int64 = 3 + 5000000000
+	var max64: uint64 = 18446744073709551615"
+		),
+		Ok(())
+	);
+	let out_of_range = |instruction: &str, expected_type: &str| {
+		let result = check(instruction);
+		assert!(
+			matches!(result, Err(Error::LiteralOutOfRange { ref data_type, .. }) if data_type.to_string() == expected_type),
+			"{} returned {:?}",
+			instruction,
+			result
+		);
+	};
+	out_of_range("var y: int8 = 300", "int8");
+	out_of_range("var y: int8 = -129", "int8");
+	out_of_range("var y: uint8 = -1", "uint8");
+	out_of_range("var y: int = 3000000000", "int");
+	out_of_range("var y: uint16 = x + 70000", "uint8");
+	out_of_range("var y: float32 = 1e39", "float32");
+	out_of_range("var y: int = 99999999999 + 3", "int");
+	out_of_range("var y: int = 3 + 99999999999", "int");
+	out_of_range("var y: int8 = 1 + 200", "int8");
+	out_of_range("var y: int8 = 200 + 1", "int8");
+	out_of_range("var c: bool = 99999999999 + 3 > 1", "int");
+	out_of_range("var y: int8 = 128i8", "int8");
+	out_of_range("var y: uint8 = -1u8", "uint8");
+	out_of_range("var y: int64 = 9223372036854775808", "int64");
+	out_of_range("var y: int64 = -18446744073709551615", "int64");
+	out_of_range("var y: float32 = 1e39f32", "float32");
+	assert!(matches!(check("var n: nothing"), Err(Error::NoValues { .. })));
+	assert!(matches!(check("var a: arr<any>(2)"), Err(Error::NoValues { .. })));
+	assert!(matches!(type_check("def main(n: nothing) {\n}"), Err(Error::NoValues { .. })));
+	assert!(matches!(type_check("def main() {\n\treturn 1\n}"), Err(Error::TypeMismatch { .. })));
+	assert_eq!(type_check("def main(n: int) {\n\tif n < 0 {\n\t\treturn\n\t}\n\treturn\n}"), Ok(()));
+	assert!(matches!(
+		type_check("def main(): int {\n\treturn\n}"),
+		Err(Error::TypeMismatch { actual: DataType::Basic(BasicDataType::Nothing), .. })
+	));
+}
+
+/// Boilerplate code for type checking source code, which must be free of syntax errors.
+fn type_check(source_code: &str) -> Result<(), Error> {
+	let source = Arc::new(Source::new("testfile".to_owned(), source_code.to_owned()));
+	let (tokens, lexer_errors) = Lexer::new(&source).tokenize();
+	assert_eq!(lexer_errors, Vec::new());
+	let (nodes, parser_errors) = Parser::new(tokens.into_iter(), source.clone()).parse();
+	assert_eq!(parser_errors, Vec::new());
+	let symbol_table = SymbolTable::global_symbol_scan(nodes.iter().map(|node| &node.node)).unwrap();
+	TypeChecker::type_check(symbol_table, nodes.iter().map(|node| &node.node))
+}
@@ -9,11 +9,14 @@ use super::{Error, SymbolTable, Variable};
 use crate::{
 	ast::{
 		self,
-		expression::{BinaryExpression, FunctionCall, Number, NumberKind},
+		expression::{
+			BinaryExpression, BinaryOperator, FieldAccess, FunctionCall, Index, Number, NumberKind, StringLiteral,
+			StringPart, StructLiteral, UnaryExpression, UnaryOperator,
+		},
 		statement::{BasicDataType, DataType},
 		Expression, FunctionDefinition,
 	},
-	source::PositionContainer,
+	source::{PositionContainer, SourcePositionRange},
 };
 
 /// Stores all variables declared in this call stack frame.
@@ -28,19 +31,32 @@ pub struct TypeChecker {
 	pub variables: HashMap<String, Arc<Variable>>,
 	/// List of stack frames, each containing the variables declared in that scope.
 	pub call_stack: Vec<CallStackFrame>,
+	/// Labels of the loops enclosing the current instruction, from the outermost to the innermost loop.
+	pub loop_labels: Vec<Option<PositionContainer<String>>>,
+	/// Return type of the function being type checked, which is `nothing` if the function does not return a value.
+	pub return_type: DataType,
 }
 
 impl TypeChecker {
+	/// Creates a type checker for the globally defined structs and functions in the `symbol_table`, with an empty
+	/// global scope.
+	pub fn new(symbol_table: SymbolTable) -> Self {
+		Self {
+			symbol_table,
+			variables: HashMap::new(),
+			call_stack: vec![CallStackFrame::new()],
+			loop_labels: Vec::new(),
+			return_type: DataType::Basic(BasicDataType::Nothing),
+		}
+	}
+
 	/// Checks that all types in statements and expressions match.
 	#[tracing::instrument(skip_all)]
 	pub fn type_check<'a>(
 		symbol_table: SymbolTable,
 		ast_nodes: impl Iterator<Item = &'a ast::Node>,
 	) -> Result<(), Error> {
-		let mut type_check = Self { symbol_table, variables: HashMap::new(), call_stack: Vec::new() };
-
-		type_check.call_stack.push(CallStackFrame::new());
-
+		let mut type_check = Self::new(symbol_table);
 		for ast_node in ast_nodes {
 			type_check.ast_node(ast_node)?;
 		}
@@ -51,23 +67,33 @@ impl TypeChecker {
 	fn ast_node(&mut self, node: &ast::Node) -> Result<(), Error> {
 		match node {
 			ast::Node::Function(function) => self.function(function),
-			ast::Node::Struct(struct_) => Ok(()),
-			ast::Node::FunctionPrototype(_) => Ok(()),
-			_ => todo!(),
+			ast::Node::Struct(struct_) => {
+				struct_.fields.iter().try_for_each(|field| Self::expect_values(&field.data_type))
+			},
+			ast::Node::FunctionPrototype(prototype) => {
+				prototype.args.iter().try_for_each(|arg| Self::expect_values(&arg.data_type))
+			},
 		}
 	}
 
 	/// Type checks each instruction in the given function.
 	#[tracing::instrument(skip_all, fields(name = function.prototype.name.deref()))]
 	fn function(&mut self, function: &FunctionDefinition) -> Result<(), Error> {
+		self.return_type = function
+			.prototype
+			.return_type
+			.as_ref()
+			.map_or(DataType::Basic(BasicDataType::Nothing), |return_type| return_type.value.clone());
+
 		// Add the function's arguments to the symbol table
-		self.call_stack.push(CallStackFrame::new());
+		self.push_call_stack_frame();
 		for arg in &function.prototype.args {
-			self.add_variable(Arc::new(Variable { name: arg.name.clone(), type_: arg.data_type.value.clone() }))?;
+			Self::expect_values(&arg.data_type)?;
+			self.add_variable(Arc::new(Variable { name: arg.name.clone(), type_: arg.data_type.value.clone() }));
 		}
 
 		// Type check the function's body
-		for instruction in &function.body {
+		for instruction in &function.body.instructions {
 			self.instruction(instruction)?;
 		}
 
@@ -82,6 +108,7 @@ impl TypeChecker {
 			ast::Instruction::Statement(statement) => self.statement(statement),
 			ast::Instruction::IfElse(if_else) => self.if_else(if_else),
 			ast::Instruction::WhileLoop(while_loop) => self.while_loop(while_loop),
+			ast::Instruction::ForLoop(for_loop) => self.for_loop(for_loop),
 		}
 	}
 
@@ -89,13 +116,29 @@ impl TypeChecker {
 	fn expression(&mut self, expression: &ast::Expression) -> Result<(), Error> {
 		match expression {
 			ast::Expression::BinaryExpression(binary_expression) => {
-				self.infer_binary_expression_type(binary_expression).map(|_expression_type| ())
+				self.infer_binary_expression_type(binary_expression, None).map(|_expression_type| ())
+			},
+			ast::Expression::Unary(unary_expression) => {
+				self.infer_unary_expression_type(unary_expression).map(|_expression_type| ())
 			},
 			ast::Expression::FunctionCall(function_call) => {
-				self.infer_function_call_return_type(function_call).map(|return_type| ())
+				self.infer_function_call_return_type(function_call).map(|_return_type| ())
+			},
+			ast::Expression::FieldAccess(field_access) => {
+				self.infer_field_access_type(field_access).map(|_field_type| ())
+			},
+			ast::Expression::StructLiteral(struct_literal) => {
+				self.infer_struct_literal_type(struct_literal).map(|_struct_type| ())
+			},
+			ast::Expression::Index(index) => self.infer_index_type(index).map(|_element_type| ()),
+			ast::Expression::Alloc(_) => Ok(()),
+			ast::Expression::String(string_literal) => {
+				self.infer_string_literal_type(string_literal).map(|_string_type| ())
 			},
 			ast::Expression::Number(_) => Ok(()),
-			ast::Expression::Variable(_) => Ok(()),
+			ast::Expression::Variable(variable) => self.infer_variable_type(variable).map(|_variable_type| ()),
+			// Typed by where it is used, see `Self::infer_expected_type`
+			ast::Expression::Nil(_) => Ok(()),
 		}
 	}
 
@@ -106,11 +149,15 @@ impl TypeChecker {
 				self.variable_declaration(variable_declaration)
 			},
 			ast::statement::Statement::VariableAssignment(assignment) => self.variable_assignment(assignment),
-			ast::Statement::Return(expression) => self.return_(expression),
+			ast::Statement::Return(return_) => self.return_(return_),
+			ast::Statement::Delete(pointer) => self.delete(pointer),
+			ast::Statement::Break(jump) => self.jump("break", jump),
+			ast::Statement::Continue(jump) => self.jump("continue", jump),
 		}
 	}
 
-	/// Checks that the type of the expression matches that of the variable.
+	/// Checks that the type of the expression matches that of the variable. Variables without initial value are
+	/// initialized with zeros.
 	fn variable_declaration(
 		&mut self,
 		variable_declaration: &ast::statement::VariableDeclaration,
@@ -124,8 +171,13 @@ impl TypeChecker {
 			position = variable.name.position.to_string(),
 			"variable declaration"
 		);
+		Self::expect_values(&variable_declaration.data_type)?;
 
-		let inferred_type = self.infer_expression_type(&variable_declaration.value)?;
+		let Some(value) = &variable_declaration.value else {
+			return self.declare_variable(variable);
+		};
+		Self::expect_assignable(&variable.type_, variable.name.position.clone())?;
+		let inferred_type = self.infer_expected_type(&variable.type_, value)?;
 		if inferred_type != variable.type_ {
 			return Err(Error::TypeMismatch {
 				expected: variable.type_.clone(),
@@ -134,6 +186,12 @@ impl TypeChecker {
 			});
 		}
 
+		// Inferring the type of the value already type checked it
+		self.declare_variable(variable)
+	}
+
+	/// Adds a newly declared variable, which must not shadow a variable that is already in scope.
+	fn declare_variable(&mut self, variable: Arc<Variable>) -> Result<(), Error> {
 		// If there is a previous declaration of this variable, there is a name conflict.
 		let previous_declaration = self.variables.get(&variable.name.value);
 		if let Some(previous_declaration) = previous_declaration {
@@ -142,117 +200,336 @@ impl TypeChecker {
 				new_declaration: Arc::clone(&variable),
 			});
 		}
-
-		self.add_variable(variable)?;
-		// Type check the expression itself
-		// TODO: Should already be covered by the type inference of the expression, i.e. by calling `self.infer_expression_type`
-		self.expression(&variable_declaration.value)?;
+		self.add_variable(variable);
 		Ok(())
 	}
 
 	/// Adds a variable to [`Self::variables`] and [`Self::call_stack`].
-	fn add_variable(&mut self, var: Arc<Variable>) -> Result<(), Error> {
+	pub(crate) fn add_variable(&mut self, var: Arc<Variable>) {
 		self.variables.insert(var.name.value.clone(), Arc::clone(&var));
 		self.call_stack.last_mut().unwrap().insert(var);
-		Ok(())
+	}
+
+	/// Adds a new frame to the call stack, whose variables are only in scope until it is dropped.
+	pub(crate) fn push_call_stack_frame(&mut self) {
+		self.call_stack.push(CallStackFrame::new());
 	}
 
 	/// Removes one frame from the call stack and deletes all of its variables from the symbol table ([`Self::variables`]).
-	fn drop_call_stack_frame(&mut self) {
+	pub(crate) fn drop_call_stack_frame(&mut self) {
 		let frame = self.call_stack.pop().unwrap();
 		for variable in frame {
 			self.variables.remove(&variable.name.value);
 		}
 	}
 
-	/// Checks that the type of the expression matches that of the variable.
+	/// Checks that the type of the expression matches that of the assigned variable or field.
 	fn variable_assignment(&mut self, variable_assignment: &ast::statement::VariableAssignment) -> Result<(), Error> {
-		// Infer the type of the expression on the right-hand side of the assignment
-		let expression_type = self.infer_expression_type(&variable_assignment.value)?;
-		let var = Arc::new(Variable { name: variable_assignment.name.clone(), type_: expression_type.clone() });
-		tracing::debug!(var = var.to_string(), position = var.name.position.to_string(), "variable assignment");
+		// Look up the type of the variable in the symbol table, or the type of the field in its struct
+		let target_type = self.infer_expression_type(&variable_assignment.target)?;
+		let target_position = variable_assignment.target.source_position();
+		if !Self::is_addressable(&variable_assignment.target) {
+			return Err(Error::NotAddressable { position: target_position });
+		}
+		Self::expect_assignable(&target_type, target_position.clone())?;
 
-		// Look up the type of the variable in the symbol table
-		let variable_type =
-			self.variables.get(&var.name.value).ok_or(Error::UndeclaredVariable { name: var.name.clone() })?;
+		// Infer the type of the expression on the right-hand side of the assignment
+		let expression_type = self.infer_expected_type(&target_type, &variable_assignment.value)?;
+		tracing::debug!(type_ = expression_type.to_string(), position = target_position.to_string(), "assignment");
 
-		if expression_type != variable_type.type_ {
+		if expression_type != target_type {
 			// Cannot assign an expression to a variable of different type
 			return Err(Error::TypeMismatch {
-				expected: variable_type.type_.clone(),
-				position: variable_assignment.name.position.clone(),
-				actual: expression_type.clone(),
+				expected: target_type,
+				position: target_position,
+				actual: expression_type,
 			});
 		}
+		Ok(())
+	}
 
-		self.add_variable(var)?;
-		self.expression(&variable_assignment.value)?;
+	/// Checks that a value of type `data_type` can be assigned as a whole. Arrays cannot, since they are emitted as C
+	/// arrays, which only their elements can be assigned to.
+	fn expect_assignable(data_type: &DataType, position: SourcePositionRange) -> Result<(), Error> {
+		if let DataType::Array { .. } = data_type {
+			return Err(Error::IllegalOperandType { operator: "=".to_owned(), actual: data_type.clone(), position });
+		}
+		Ok(())
+	}
+
+	/// Checks that values of `data_type` exist, so that it can be the type of a variable, argument or field.
+	fn expect_values(data_type: &PositionContainer<DataType>) -> Result<(), Error> {
+		if !data_type.has_values() {
+			return Err(Error::NoValues { data_type: data_type.value.clone(), position: data_type.position.clone() });
+		}
 		Ok(())
 	}
 
 	/// Checks that the return type of the function matches the type of the return expression.
-	fn return_(&mut self, expression: &Expression) -> Result<(), Error> {
-		let return_type = self.infer_expression_type(expression)?;
-		// TODO: Check that the return type matches the function's return type
-		tracing::warn!("TODO: Check that the return type {:?} matches the function's return type", return_type);
+	fn return_(&mut self, return_: &ast::statement::Return) -> Result<(), Error> {
+		let return_type = self.return_type.clone();
+		let Some(value) = &return_.value else {
+			// Without value, only functions returning `nothing` may be left
+			let nothing = DataType::Basic(BasicDataType::Nothing);
+			Self::expect_type(return_type, nothing, return_.position.clone())?;
+			return Ok(());
+		};
+		let actual = self.infer_expected_type(&return_type, value)?;
+		Self::expect_type(return_type, actual, value.source_position())?;
+		Ok(())
+	}
+
+	/// Checks that only pointers are deleted.
+	fn delete(&mut self, pointer: &Expression) -> Result<(), Error> {
+		let pointer_type = self.infer_expression_type(pointer)?;
+		if !matches!(pointer_type, DataType::Pointer(_)) {
+			return Err(Error::IllegalOperandType {
+				operator: "del".to_owned(),
+				actual: pointer_type,
+				position: pointer.source_position(),
+			});
+		}
+		Ok(())
+	}
+
+	/// Checks that a `break` or `continue` is inside a loop, and that its label belongs to an enclosing loop.
+	fn jump(&self, keyword: &'static str, jump: &ast::statement::Jump) -> Result<(), Error> {
+		if self.loop_labels.is_empty() {
+			return Err(Error::JumpOutsideLoop { keyword, position: jump.position.clone() });
+		}
+		let Some(label) = &jump.label else {
+			return Ok(());
+		};
+		let is_enclosing_loop = |loop_label: &Option<PositionContainer<String>>| {
+			loop_label.as_ref().is_some_and(|loop_label| loop_label.value == label.value)
+		};
+		if !self.loop_labels.iter().any(is_enclosing_loop) {
+			return Err(Error::UnknownLabel { label: label.clone() });
+		}
 		Ok(())
 	}
 
 	/// Type checks an if-else block.
+	///
+	/// Each branch has its own scope, so variables declared in one branch are neither visible in the other branches
+	/// nor after the if-else. The conditions are checked in the scope around the if-else.
 	fn if_else(&mut self, if_else: &ast::IfElse) -> Result<(), Error> {
 		// if block, always present
-		self.expression(&if_else.condition)?;
+		self.condition(&if_else.condition)?;
+		self.block(&if_else.if_true)?;
 
-		self.call_stack.push(CallStackFrame::new());
-		for instruction in &if_else.if_true {
-			self.instruction(instruction)?;
+		// else if blocks, optional
+		for else_if in &if_else.else_ifs {
+			self.condition(&else_if.condition)?;
+			self.block(&else_if.body)?;
 		}
-		self.drop_call_stack_frame();
 
 		// else block, optional
-		if if_else.if_false.is_empty() {
-			return Ok(());
-		}
-		self.call_stack.push(CallStackFrame::new());
-		for instruction in &if_else.if_false {
-			self.instruction(instruction)?;
-		}
-		self.drop_call_stack_frame();
-
-		Ok(())
+		self.block(&if_else.if_false)
 	}
 
 	/// Type checks a while loop.
 	fn while_loop(&mut self, while_loop: &ast::WhileLoop) -> Result<(), Error> {
-		self.expression(&while_loop.condition)?;
+		self.condition(&while_loop.condition)?;
+		self.loop_body(&while_loop.label, &while_loop.body)
+	}
 
-		self.call_stack.push(CallStackFrame::new());
-		for instruction in &while_loop.body {
+	/// Checks that the condition of an `if`, `else if` or `while` is a `bool`.
+	fn condition(&self, condition: &ast::Expression) -> Result<(), Error> {
+		let actual = self.infer_expression_type(condition)?;
+		Self::expect_type(DataType::Basic(BasicDataType::Bool), actual, condition.source_position())?;
+		Ok(())
+	}
+
+	/// Type checks a for loop.
+	///
+	/// The loop variable has the type of the range bounds or of the array elements and is only visible in the body.
+	fn for_loop(&mut self, for_loop: &ast::ForLoop) -> Result<(), Error> {
+		let variable_type = match &for_loop.iterable {
+			ast::for_loop::Iterable::Range(range) => self.infer_range_type(range)?,
+			ast::for_loop::Iterable::Array(array) => match self.infer_expression_type(array)? {
+				DataType::Array { element, .. } => element.value,
+				actual => return Err(Error::NotIterable { actual, position: array.source_position() }),
+			},
+		};
+
+		self.push_call_stack_frame();
+		let variable = Arc::new(Variable { name: for_loop.variable.clone(), type_: variable_type });
+		let result = self.declare_variable(variable).and_then(|_| self.loop_body(&for_loop.label, &for_loop.body));
+		self.drop_call_stack_frame();
+		result
+	}
+
+	/// Infers the type of the integers in a range, i.e. the common type of its start, end and step.
+	///
+	/// The type is that of the start, unless the start is a number literal without suffix and the end is not.
+	pub(crate) fn infer_range_type(&self, range: &ast::for_loop::Range) -> Result<DataType, Error> {
+		let typed_bound = if range.start.is_untyped() && !range.end.is_untyped() { &range.end } else { &range.start };
+		let range_type = self.infer_expression_type(typed_bound)?;
+		if !range_type.is_integer() {
+			return Err(Error::IllegalOperandType {
+				operator: if range.inclusive { "..=" } else { ".." }.to_owned(),
+				actual: range_type,
+				position: typed_bound.source_position(),
+			});
+		}
+		for bound in [&range.start, &range.end].into_iter().chain(&range.step) {
+			let bound_type = self.infer_expected_type(&range_type, bound)?;
+			Self::expect_type(range_type.clone(), bound_type, bound.source_position())?;
+		}
+		Ok(range_type)
+	}
+
+	/// Type checks the body of a loop with the given label, in which `break` and `continue` are allowed.
+	fn loop_body(&mut self, label: &Option<PositionContainer<String>>, body: &ast::Block) -> Result<(), Error> {
+		self.loop_labels.push(label.clone());
+		let result = self.block(body);
+		self.loop_labels.pop();
+		result
+	}
+
+	/// Type checks the instructions of a block in a new scope.
+	fn block(&mut self, block: &ast::Block) -> Result<(), Error> {
+		self.push_call_stack_frame();
+		for instruction in &block.instructions {
 			self.instruction(instruction)?;
 		}
 		self.drop_call_stack_frame();
-
 		Ok(())
 	}
 
 	/// Infers the type of an expression, which can consist of binary expressions, numbers, function calls and variables.
 	pub fn infer_expression_type(&self, expression: &Expression) -> Result<DataType, Error> {
 		match expression {
-			Expression::BinaryExpression(binary_expression) => self.infer_binary_expression_type(binary_expression),
+			Expression::BinaryExpression(binary_expression) => {
+				self.infer_binary_expression_type(binary_expression, None)
+			},
+			Expression::Unary(unary_expression) => self.infer_unary_expression_type(unary_expression),
 			Expression::FunctionCall(function_call) => self.infer_function_call_return_type(function_call),
-			Expression::Number(number) => Self::number_type_inference(number),
+			Expression::FieldAccess(field_access) => self.infer_field_access_type(field_access),
+			Expression::StructLiteral(struct_literal) => self.infer_struct_literal_type(struct_literal),
+			Expression::Index(index) => self.infer_index_type(index),
+			Expression::Alloc(alloc) => Ok(DataType::Pointer(Box::new(alloc.data_type.clone()))),
+			Expression::String(string_literal) => self.infer_string_literal_type(string_literal),
+			Expression::Number(number) => Self::number_type_inference(number, false, &number.position),
 			Expression::Variable(variable) => {
 				// Here, a variables is used inside an expression. This is not about a variable declaration.
 				self.infer_variable_type(variable)
 			},
+			// The type of `nil` depends on where it is used, see `Self::infer_expected_type`
+			Expression::Nil(position) => Err(Error::UntypedNil { position: position.clone() }),
+		}
+	}
+
+	/// Infers the type of an expression used where a value of the `expected` type is needed. In contrast to
+	/// [`Self::infer_expression_type`], `nil` takes the expected type, if that is a pointer type, and number literals
+	/// without suffix take the expected integer or floating point type, if they lie within its range. This also holds
+	/// for such literals in arithmetic and bitwise expressions like `1 + 1`. Like `void *` in C, `ptr any` converts
+	/// from and to all other pointer types.
+	fn infer_expected_type(&self, expected: &DataType, expression: &Expression) -> Result<DataType, Error> {
+		let actual = match expression {
+			Expression::Nil(position) => {
+				if !expected.is_pointer() {
+					return Err(Error::NilNotPointer { expected: expected.clone(), position: position.clone() });
+				}
+				return Ok(expected.clone());
+			},
+			Expression::BinaryExpression(binary_expression) => {
+				self.infer_binary_expression_type(binary_expression, Some(expected))?
+			},
+			_ => self.infer_expression_type(expression)?,
+		};
+		let converts = match (expected, &actual) {
+			(DataType::Basic(expected), DataType::Basic(actual)) if expression.is_untyped() => {
+				let converts =
+					(expected.is_integer() && actual.is_integer()) || (expected.is_float() && actual.is_float());
+				if converts {
+					Self::expect_literal_in_range(expected, expression)?;
+				}
+				converts
+			},
+			(DataType::Pointer(_), DataType::Pointer(_)) => expected.is_any_pointer() || actual.is_any_pointer(),
+			_ => false,
+		};
+		Ok(if converts { expected.clone() } else { actual })
+	}
+
+	/// Checks that the value of the (negated) number literal `literal` lies within the range of the `expected` type.
+	fn expect_literal_in_range(expected: &BasicDataType, literal: &Expression) -> Result<(), Error> {
+		let (is_negated, number) = match literal {
+			Expression::Number(number) => (false, number),
+			Expression::Unary(unary_expression) => match &*unary_expression.operand {
+				Expression::Number(number) => (true, number),
+				_ => return Ok(()),
+			},
+			_ => return Ok(()),
+		};
+		Self::expect_number_in_range(expected, number, is_negated, literal.source_position())
+	}
+
+	/// Checks that the value of the `number`, which is negated if `is_negated`, lies within the range of the `expected`
+	/// type. `position` is the position of the (negated) number.
+	fn expect_number_in_range(
+		expected: &BasicDataType,
+		number: &Number,
+		is_negated: bool,
+		position: SourcePositionRange,
+	) -> Result<(), Error> {
+		let is_in_range = match number.value {
+			NumberKind::Int(int, _) => {
+				let value = if is_negated { -i128::from(int) } else { i128::from(int) };
+				expected.integer_range().is_none_or(|range| range.contains(&value))
+			},
+			NumberKind::Float(float, _) => expected.float_max().is_none_or(|max| float <= max),
+		};
+		if !is_in_range {
+			return Err(Error::LiteralOutOfRange { data_type: DataType::Basic(expected.clone()), position });
 		}
+		Ok(())
 	}
 
 	/// Infers the type of the left-hand and right-hand side of a binary expression,
-	/// verifies that they are equal and returns this common type.
-	fn infer_binary_expression_type(&self, binary_expression: &BinaryExpression) -> Result<DataType, Error> {
-		let lhs = self.infer_expression_type(&binary_expression.lhs)?;
-		let rhs = self.infer_expression_type(&binary_expression.rhs)?;
+	/// verifies that they are equal and returns the type of the whole expression.
+	///
+	/// Logical operators expect `bool` operands and bitwise operators as well as the modulus expect integer operands.
+	/// The other arithmetic operators expect numbers, except for `+`, which also concatenates strings. Numbers and
+	/// strings can be ordered with `<`, `>`, `<=` and `>=`, while `==` and `=/=` also compare other basic types and
+	/// pointers, but no structs or arrays. Comparisons produce a `bool`, all other operators produce the common type
+	/// of their operands.
+	fn infer_binary_expression_type(
+		&self,
+		binary_expression: &BinaryExpression,
+		expected: Option<&DataType>,
+	) -> Result<DataType, Error> {
+		let operator = &binary_expression.operator;
+		// `nil` and number literals without suffix get the type of the other operand
+		let (lhs, rhs) = match (binary_expression.lhs.is_untyped(), binary_expression.rhs.is_untyped()) {
+			(true, true) => {
+				// Both operands get the expected type of an arithmetic or bitwise expression, or the type of the lhs
+				let expected = match expected {
+					Some(expected) if !operator.is_comparison() && !operator.is_logical() => expected.clone(),
+					_ => self.infer_expression_type(&binary_expression.lhs)?,
+				};
+				(
+					self.infer_expected_type(&expected, &binary_expression.lhs)?,
+					self.infer_expected_type(&expected, &binary_expression.rhs)?,
+				)
+			},
+			(true, false) => {
+				let rhs = self.infer_expression_type(&binary_expression.rhs)?;
+				(self.infer_expected_type(&rhs, &binary_expression.lhs)?, rhs)
+			},
+			(false, _) => {
+				let lhs = self.infer_expression_type(&binary_expression.lhs)?;
+				let rhs = self.infer_expected_type(&lhs, &binary_expression.rhs)?;
+				(lhs, rhs)
+			},
+		};
+		if binary_expression.operator.is_logical() {
+			Self::expect_type(DataType::Basic(BasicDataType::Bool), lhs, binary_expression.lhs.source_position())?;
+			Self::expect_type(DataType::Basic(BasicDataType::Bool), rhs, binary_expression.rhs.source_position())?;
+			return Ok(DataType::Basic(BasicDataType::Bool));
+		}
 		if lhs != rhs {
 			return Err(Error::TypeMismatch {
 				expected: lhs,
@@ -260,9 +537,92 @@ impl TypeChecker {
 				actual: rhs,
 			});
 		}
+		let is_string = lhs == DataType::Basic(BasicDataType::Str);
+		let is_legal = match operator.value {
+			BinaryOperator::Equal | BinaryOperator::NotEqual => {
+				matches!(lhs, DataType::Basic(_) | DataType::Pointer(_))
+			},
+			_ if operator.is_comparison() => lhs.is_numeric() || is_string,
+			_ if operator.is_integer_only() => lhs.is_integer(),
+			BinaryOperator::Add => lhs.is_numeric() || is_string,
+			_ => lhs.is_numeric(),
+		};
+		if !is_legal {
+			return Err(Error::IllegalOperandType {
+				operator: operator.value.to_string(),
+				actual: lhs,
+				position: operator.position.clone(),
+			});
+		}
+		if operator.is_comparison() {
+			return Ok(DataType::Basic(BasicDataType::Bool));
+		}
 		Ok(lhs)
 	}
 
+	/// Infers the type of the operand of a unary expression, verifies that the operator can be applied to it and
+	/// returns the type of the whole expression.
+	///
+	/// `ref` produces a pointer to its operand, which needs to have a location in memory. `deref` produces the value
+	/// its pointer operand points to.
+	fn infer_unary_expression_type(&self, unary_expression: &UnaryExpression) -> Result<DataType, Error> {
+		let operand = match (&*unary_expression.operator, &*unary_expression.operand) {
+			// The range of a negated number is checked as a whole, so that e.g. `-128i8` is allowed
+			(UnaryOperator::Negate, Expression::Number(number)) => {
+				Self::number_type_inference(number, true, &unary_expression.source_position())?
+			},
+			(_, operand) => self.infer_expression_type(operand)?,
+		};
+		let is_legal = match *unary_expression.operator {
+			UnaryOperator::Reference => {
+				if !Self::is_addressable(&unary_expression.operand) {
+					return Err(Error::NotAddressable { position: unary_expression.operand.source_position() });
+				}
+				let position = unary_expression.operand.source_position();
+				return Ok(DataType::Pointer(Box::new(PositionContainer::new(operand, position))));
+			},
+			UnaryOperator::Dereference => match &operand {
+				DataType::Pointer(pointee) => return Ok(pointee.value.clone()),
+				_ => false,
+			},
+			UnaryOperator::Negate => operand.is_numeric(),
+			UnaryOperator::Not => {
+				return Self::expect_type(
+					DataType::Basic(BasicDataType::Bool),
+					operand,
+					unary_expression.operand.source_position(),
+				)
+			},
+			UnaryOperator::Complement => operand.is_integer(),
+		};
+		if !is_legal {
+			return Err(Error::IllegalOperandType {
+				operator: unary_expression.operator.value.to_string(),
+				actual: operand,
+				position: unary_expression.source_position(),
+			});
+		}
+		Ok(operand)
+	}
+
+	/// Checks whether `expression` denotes a location in memory, whose address can be taken with `ref` and which can
+	/// be assigned to.
+	fn is_addressable(expression: &Expression) -> bool {
+		match expression {
+			Expression::Variable(_) | Expression::FieldAccess(_) | Expression::Index(_) => true,
+			Expression::Unary(unary_expression) => *unary_expression.operator == UnaryOperator::Dereference,
+			_ => false,
+		}
+	}
+
+	/// Returns `actual` if it equals `expected`, and a [`Error::TypeMismatch`] at `position` otherwise.
+	fn expect_type(expected: DataType, actual: DataType, position: SourcePositionRange) -> Result<DataType, Error> {
+		if actual != expected {
+			return Err(Error::TypeMismatch { expected, position, actual });
+		}
+		Ok(actual)
+	}
+
 	/// Infers the type of a variable by looking it up in [`Self::variables`].
 	fn infer_variable_type(&self, variable: &PositionContainer<String>) -> Result<DataType, Error> {
 		self.variables
@@ -271,6 +631,134 @@ impl TypeChecker {
 			.ok_or(Error::UndeclaredVariable { name: variable.clone() })
 	}
 
+	/// Infers the type of a field access by looking up the field in the struct of the base in
+	/// [`SymbolTable::structs`]. If the base is a pointer to a struct, the field of the pointed-to struct is accessed.
+	///
+	/// Strings have the built-in fields `_len` and `_cstr`, to pass them to C functions.
+	fn infer_field_access_type(&self, field_access: &FieldAccess) -> Result<DataType, Error> {
+		let base_type = self.infer_expression_type(&field_access.base)?;
+		let owner = match &base_type {
+			DataType::Pointer(pointee) => &pointee.value,
+			other => other,
+		};
+		let struct_name = match owner {
+			DataType::Struct(struct_name) => struct_name,
+			DataType::Basic(BasicDataType::Str) => return Self::infer_string_field_type(field_access),
+			_ => return Err(Self::no_fields(base_type.clone(), field_access)),
+		};
+		let struct_ = self.symbol_table.structs.get(struct_name).ok_or_else(|| Error::UndefinedStruct {
+			name: struct_name.clone(),
+			position: field_access.base.source_position(),
+		})?;
+		struct_
+			.fields
+			.iter()
+			.find(|field| field.name.value == field_access.field.value)
+			.map(|field| field.data_type.value.clone())
+			.ok_or_else(|| Error::UnknownField { struct_name: struct_name.clone(), field: field_access.field.clone() })
+	}
+
+	/// Infers the type of an array element accessed by an index, which needs to be an integer.
+	///
+	/// Indexes given as number literals are checked to be within the bounds of the array.
+	fn infer_index_type(&self, index: &Index) -> Result<DataType, Error> {
+		let base_type = self.infer_expression_type(&index.base)?;
+		let DataType::Array { element, length } = base_type else {
+			return Err(Error::IllegalOperandType {
+				operator: "@".to_owned(),
+				actual: base_type,
+				position: index.base.source_position(),
+			});
+		};
+		let index_type = self.infer_expression_type(&index.index)?;
+		if !index_type.is_integer() {
+			return Err(Error::IllegalOperandType {
+				operator: "@".to_owned(),
+				actual: index_type,
+				position: index.index.source_position(),
+			});
+		}
+		if let Expression::Number(number) = &*index.index {
+			if let NumberKind::Int(int, _) = number.value {
+				if int >= length {
+					return Err(Error::IndexOutOfBounds { index: int, length, position: number.position.clone() });
+				}
+			}
+		}
+		Ok(element.value)
+	}
+
+	/// Checks that a struct literal initializes each field of its struct exactly once with a value of the field's
+	/// type, and returns the type of the struct. Array fields may be left out, since there are no array literals.
+	fn infer_struct_literal_type(&self, struct_literal: &StructLiteral) -> Result<DataType, Error> {
+		let struct_name = &struct_literal.name.value;
+		let struct_ = self.symbol_table.structs.get(struct_name).ok_or_else(|| Error::UndefinedStruct {
+			name: struct_name.clone(),
+			position: struct_literal.name.position.clone(),
+		})?;
+
+		let mut initialized = HashSet::new();
+		for initializer in &struct_literal.fields {
+			let field =
+				struct_.fields.iter().find(|field| field.name.value == initializer.name.value).ok_or_else(|| {
+					Error::UnknownField { struct_name: struct_name.clone(), field: initializer.name.clone() }
+				})?;
+			if !initialized.insert(&field.name.value) {
+				return Err(Error::DuplicateField { field: initializer.name.clone() });
+			}
+			let value_type = self.infer_expected_type(&field.data_type.value, &initializer.value)?;
+			Self::expect_type(field.data_type.value.clone(), value_type, initializer.value.source_position())?;
+		}
+
+		// There are no array literals, so array fields may be left out and are zero-initialized
+		if let Some(missing) = struct_.fields.iter().find(|field| {
+			!initialized.contains(&field.name.value) && !matches!(field.data_type.value, DataType::Array { .. })
+		}) {
+			return Err(Error::MissingField {
+				struct_name: struct_name.clone(),
+				field: missing.name.value.clone(),
+				position: struct_literal.name.position.clone(),
+			});
+		}
+		Ok(DataType::Struct(struct_name.clone()))
+	}
+
+	/// Infers the type of the built-in field of a string, which is either its length `_len` in bytes, or its
+	/// null-terminated UTF-8 bytes `_cstr`.
+	fn infer_string_field_type(field_access: &FieldAccess) -> Result<DataType, Error> {
+		match field_access.field.value.as_str() {
+			"_len" => Ok(DataType::Basic(BasicDataType::Int)),
+			"_cstr" => Ok(DataType::Pointer(Box::new(PositionContainer::new(
+				DataType::Basic(BasicDataType::Char),
+				field_access.field.position.clone(),
+			)))),
+			_ => Err(Error::UnknownField { struct_name: "str".to_owned(), field: field_access.field.clone() }),
+		}
+	}
+
+	/// Checks that all values interpolated into a string literal can be converted to a string.
+	fn infer_string_literal_type(&self, string_literal: &StringLiteral) -> Result<DataType, Error> {
+		for part in &string_literal.parts {
+			let StringPart::Interpolation(value) = part else {
+				continue;
+			};
+			let value_type = self.infer_expression_type(value)?;
+			if !matches!(&value_type, DataType::Basic(basic) if basic.has_values()) {
+				return Err(Error::NotInterpolatable { actual: value_type, position: value.source_position() });
+			}
+		}
+		Ok(DataType::Basic(BasicDataType::Str))
+	}
+
+	/// Returns the error for accessing a field of `base_type`, which is no struct.
+	fn no_fields(base_type: DataType, field_access: &FieldAccess) -> Error {
+		Error::IllegalOperandType {
+			operator: ".".to_owned(),
+			actual: base_type,
+			position: field_access.base.source_position(),
+		}
+	}
+
 	/// Looks up the return type of the function and thereby checks that the types of the parameters supplied in the `function_call`
 	/// match the types of the arguments of the defined function in the [symbol table](Self::symbol_table).
 	fn infer_function_call_return_type(&self, function_call: &FunctionCall) -> Result<DataType, Error> {
@@ -292,7 +780,7 @@ impl TypeChecker {
 
 		// Check that the types of supplied parameters and expected arguments match.
 		for (param, arg) in iter::zip(&function_call.params, &function_definition.args) {
-			let param_type = self.infer_expression_type(param)?;
+			let param_type = self.infer_expected_type(&arg.data_type.value, param)?;
 			if param_type != arg.data_type.value {
 				return Err(Error::TypeMismatch {
 					expected: arg.data_type.value.clone(),
@@ -305,15 +793,26 @@ impl TypeChecker {
 		Ok(function_definition
 			.return_type
 			.as_ref()
-			.map(|return_type| return_type.value.clone())
-			.expect("Function without return value not supported yet"))
+			.map_or(DataType::Basic(BasicDataType::Nothing), |return_type| return_type.value.clone()))
 	}
 
-	/// Infers the type of a number expression.
-	fn number_type_inference(number: &Number) -> Result<DataType, Error> {
-		match number.value {
-			NumberKind::Int(_) => Ok(DataType::Basic(BasicDataType::Int)),
-			NumberKind::Float(_) => Ok(DataType::Basic(BasicDataType::Float)),
+	/// Infers the type of a number expression from its suffix. Without suffix, it is an `int` or a `float`.
+	///
+	/// A number with suffix, which is negated if `is_negated`, must lie within the range of its type. The range of a
+	/// number without suffix is checked when it is converted to its expected type, see [`Self::infer_expected_type`].
+	fn number_type_inference(
+		number: &Number,
+		is_negated: bool,
+		position: &SourcePositionRange,
+	) -> Result<DataType, Error> {
+		let (NumberKind::Int(_, suffix) | NumberKind::Float(_, suffix)) = number.value;
+		let basic_data_type = match number.value {
+			NumberKind::Int(..) => suffix.map_or(BasicDataType::Int, BasicDataType::from),
+			NumberKind::Float(..) => suffix.map_or(BasicDataType::Float, BasicDataType::from),
+		};
+		if suffix.is_some() {
+			Self::expect_number_in_range(&basic_data_type, number, is_negated, position.clone())?;
 		}
+		Ok(DataType::Basic(basic_data_type))
 	}
 }
@@ -2,19 +2,26 @@
 //!
 //! Every char in source code belongs to a [`Source`] (e.g. a file) and has a [`Position`] in this file.
 //! To make it more ergonomically to work with positions, the [`PositionContainer`] wraps an element
-//! with its [`Position`].
+//! with its [`SourcePositionRange`], whose lines and columns are only resolved when needed. Tokens use the more
+//! compact [`Span`]s instead, which only store byte offsets and are converted via [`Source::locate`].
 
 mod position;
 mod position_container;
 mod position_range;
 mod source_position;
+mod span;
+mod spanned;
 
 use std::{fmt, sync::Arc};
 
+use unicode_segmentation::UnicodeSegmentation;
+
 pub use position::Position;
 pub use position_container::PositionContainer;
 pub use position_range::PositionRange;
 pub use source_position::SourcePositionRange;
+pub use span::Span;
+pub use spanned::Spanned;
 
 /// Contains the source code of a file.
 ///
@@ -23,8 +30,10 @@ pub use source_position::SourcePositionRange;
 pub struct Source {
 	/// Filename.
 	pub name: String,
-	/// Content as chars.
-	pub text: Arc<[char]>,
+	/// Content as UTF-8 string.
+	pub text: String,
+	/// Byte offsets of the starts of all lines, for quickly converting offsets to lines.
+	line_starts: Vec<usize>,
 }
 
 impl Source {
@@ -37,141 +46,183 @@ impl Source {
 	///
 	/// let source = Source::new("file.name".to_owned(), "ab\nc".to_owned());
 	/// assert_eq!(source.name, "file.name");
-	/// assert_eq!(&*source.text, &['a', 'b', '\n', 'c']);
+	/// assert_eq!(source.text, "ab\nc");
 	/// ```
 	pub fn new(name: String, text: String) -> Self {
-		Self { name, text: text.chars().collect() }
+		let line_starts = std::iter::once(0).chain(text.match_indices('\n').map(|(newline, _)| newline + 1)).collect();
+		Self { name, text, line_starts }
 	}
 
-	/// Creates an iterator over the [`Symbol`]s of the source code.
+	/// Returns the [`Position`] of the char starting at byte `offset`.
 	///
 	/// # Example
 	///
 	/// ```
-	/// use std::sync::Arc;
+	/// use fortytwolang::source::{Position, Source};
 	///
-	/// use fortytwolang::source::{
-	/// 	Position, PositionContainer, PositionRange, Source, SourcePositionRange,
-	/// };
-	///
-	/// let source = Arc::new(Source::new("file.name".to_owned(), "text...".to_owned()));
-	/// let mut iter = Arc::clone(&source).iter();
-	/// let expected = Some(PositionContainer::new(
-	/// 	't',
-	/// 	SourcePositionRange {
-	/// 		source: Arc::clone(&source),
-	/// 		position: PositionRange {
-	/// 			start: Position { line: 1, column: 1, offset: 0 },
-	/// 			end: Position { line: 1, column: 1, offset: 0 },
-	/// 		},
-	/// 	},
-	/// ));
-	/// assert_eq!(iter.next(), expected);
+	/// let source = Source::new("file.name".to_owned(), "ä\nbc".to_owned());
+	/// assert_eq!(source.position(4), Position { line: 2, column: 2, offset: 4 });
 	/// ```
-	pub fn iter(self: Arc<Self>) -> impl Iterator<Item = Symbol> {
-		SourceIter { source: self, position: Position::default() }
+	pub fn position(&self, offset: usize) -> Position {
+		let line = self.line_starts.partition_point(|&line_start| line_start <= offset);
+		let column = self.text[self.line_starts[line - 1]..offset].chars().count() + 1;
+		Position { line, column, offset }
 	}
-}
 
-impl fmt::Debug for Source {
-	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-		f.debug_struct("Source").field("name", &self.name).finish()
+	/// Converts a [`Span`] into an inclusive [`PositionRange`] from its first to its last char.
+	pub fn position_range(&self, span: Span) -> PositionRange {
+		let range = span.range();
+		let last_char_len = self.text[range.clone()].chars().next_back().map_or(0, char::len_utf8);
+		PositionRange { start: self.position(range.start), end: self.position(range.end - last_char_len) }
 	}
-}
 
-/// A char with its position in the source code.
-pub type Symbol = PositionContainer<char>;
-
-/// Iterator over the chars of a source code.
-struct SourceIter {
-	source: Arc<Source>,
-	position: Position,
-}
-
-impl Iterator for SourceIter {
-	type Item = Symbol;
+	/// Converts a [`Span`] into a [`SourcePositionRange`] in this source. Lines and columns are not resolved until
+	/// they are requested.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use std::sync::Arc;
+	///
+	/// use fortytwolang::source::{Position, PositionRange, Source, Span};
+	///
+	/// let source = Arc::new(Source::new("file.name".to_owned(), "var größe".to_owned()));
+	/// let position = source.locate(Span::new(4, 11));
+	/// assert_eq!(
+	/// 	position.position(),
+	/// 	PositionRange {
+	/// 		start: Position { line: 1, column: 5, offset: 4 },
+	/// 		end: Position { line: 1, column: 9, offset: 10 },
+	/// 	}
+	/// );
+	/// assert_eq!(position.get_affected_code(), "größe");
+	/// ```
+	pub fn locate(self: &Arc<Self>, span: Span) -> SourcePositionRange {
+		SourcePositionRange { source: Arc::clone(self), span }
+	}
 
-	fn next(&mut self) -> Option<Self::Item> {
-		let char_ = *self.source.text.get(self.position.offset)?;
+	/// Returns the given (1-based) line without its line break.
+	pub fn line(&self, line: usize) -> &str {
+		let start = self.line_starts[line - 1];
+		let end = self.line_starts.get(line).map_or(self.text.len(), |next_line_start| next_line_start - 1);
+		self.text[start..end].trim_end_matches('\r')
+	}
 
-		let item = PositionContainer::new(
-			char_,
-			SourcePositionRange {
-				source: Arc::clone(&self.source),
-				position: PositionRange { start: self.position, end: self.position },
-			},
-		);
+	/// Returns the column of `position` counted in UTF-16 code units, as expected by most editors.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use fortytwolang::source::{Position, Source};
+	///
+	/// let source = Source::new("file.name".to_owned(), "a😀b".to_owned());
+	/// let b = Position { line: 1, column: 3, offset: 5 };
+	/// assert_eq!(source.utf16_column(b), 4);
+	/// ```
+	pub fn utf16_column(&self, position: Position) -> usize {
+		let line_start = self.line_starts[position.line - 1];
+		self.text[line_start..position.offset].encode_utf16().count() + 1
+	}
 
-		self.position.offset += 1;
-		if char_ == '\n' {
-			self.position.line += 1;
-			self.position.column = 1;
-		} else {
-			self.position.column += 1;
-		}
+	/// Returns the column of `position` counted in grapheme clusters, i.e. user-perceived characters.
+	///
+	/// A position inside a grapheme cluster gets the column of the whole cluster.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use fortytwolang::source::{Position, Source};
+	///
+	/// // `e` followed by a combining acute accent
+	/// let source = Source::new("file.name".to_owned(), "e\u{301}x".to_owned());
+	/// let accent = Position { line: 1, column: 2, offset: 1 };
+	/// let x = Position { line: 1, column: 3, offset: 3 };
+	/// assert_eq!(source.grapheme_column(accent), 1);
+	/// assert_eq!(source.grapheme_column(x), 2);
+	/// ```
+	pub fn grapheme_column(&self, position: Position) -> usize {
+		let line_start = self.line_starts[position.line - 1];
+		self.text[line_start..]
+			.grapheme_indices(true)
+			.take_while(|(index, _)| line_start + index <= position.offset)
+			.count()
+			.max(1)
+	}
+}
 
-		Some(item)
+impl fmt::Debug for Source {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Source").field("name", &self.name).finish()
 	}
 }
 
 #[cfg(test)]
 mod tests {
-	use std::sync::Arc;
-
 	use super::*;
 
-	#[test]
-	fn test_source_iter() {
-		let source = Arc::new(Source::new("file.name".to_owned(), "ab\nc".to_owned()));
-
-		let mut iter = Arc::clone(&source).iter();
+	/// Returns the positions of all chars in `source`.
+	fn char_positions(source: &Source) -> Vec<(char, Position)> {
+		source.text.char_indices().map(|(offset, char_)| (char_, source.position(offset))).collect()
+	}
 
+	#[test]
+	fn test_position() {
+		let source = Source::new("file.name".to_owned(), "ab\nc".to_owned());
 		assert_eq!(
-			iter.next(),
-			Some(PositionContainer::new(
-				'a',
-				SourcePositionRange { source: Arc::clone(&source), position: PositionRange::default() }
-			))
-		);
-		assert_eq!(
-			iter.next(),
-			Some(PositionContainer::new(
-				'b',
-				SourcePositionRange {
-					source: Arc::clone(&source),
-					position: PositionRange {
-						start: Position { line: 1, column: 2, offset: 1 },
-						end: Position { line: 1, column: 2, offset: 1 },
-					},
-				}
-			))
+			char_positions(&source),
+			vec![
+				('a', Position { line: 1, column: 1, offset: 0 }),
+				('b', Position { line: 1, column: 2, offset: 1 }),
+				('\n', Position { line: 1, column: 3, offset: 2 }),
+				('c', Position { line: 2, column: 1, offset: 3 }),
+			]
 		);
+	}
+
+	/// Tests that offsets are counted in bytes, but columns in chars.
+	#[test]
+	fn test_position_non_ascii() {
+		let source = Source::new("file.name".to_owned(), "ä😀\nß".to_owned());
 		assert_eq!(
-			iter.next(),
-			Some(PositionContainer::new(
-				'\n',
-				SourcePositionRange {
-					source: Arc::clone(&source),
-					position: PositionRange {
-						start: Position { line: 1, column: 3, offset: 2 },
-						end: Position { line: 1, column: 3, offset: 2 },
-					},
-				}
-			))
+			char_positions(&source),
+			vec![
+				('ä', Position { line: 1, column: 1, offset: 0 }),
+				('😀', Position { line: 1, column: 2, offset: 2 }),
+				('\n', Position { line: 1, column: 3, offset: 6 }),
+				('ß', Position { line: 2, column: 1, offset: 7 }),
+			]
 		);
+	}
+
+	#[test]
+	fn test_line() {
+		let source = Source::new("file.name".to_owned(), "first\r\nsecond\n\nlast".to_owned());
+		let lines: Vec<&str> = (1..=4).map(|line| source.line(line)).collect();
+		assert_eq!(lines, vec!["first", "second", "", "last"]);
+	}
+
+	#[test]
+	fn test_utf16_and_grapheme_columns() {
+		// The flag consists of two chars, each encoded as two UTF-16 code units
+		let source = Source::new("file.name".to_owned(), "x\n🇩🇪ö = 1".to_owned());
+
+		let columns: Vec<(usize, usize, usize)> = char_positions(&source)
+			.into_iter()
+			.map(|(_, position)| (position.column, source.utf16_column(position), source.grapheme_column(position)))
+			.collect();
 		assert_eq!(
-			iter.next(),
-			Some(PositionContainer::new(
-				'c',
-				SourcePositionRange {
-					source: Arc::clone(&source),
-					position: PositionRange {
-						start: Position { line: 2, column: 1, offset: 3 },
-						end: Position { line: 2, column: 1, offset: 3 },
-					},
-				}
-			))
+			columns,
+			vec![
+				(1, 1, 1), // x
+				(2, 2, 2), // \n
+				(1, 1, 1), // 🇩
+				(2, 3, 1), // 🇪
+				(3, 5, 2), // ö
+				(4, 6, 3), // ' '
+				(5, 7, 4), // =
+				(6, 8, 5), // ' '
+				(7, 9, 6), // 1
+			]
 		);
-		assert_eq!(iter.next(), None);
 	}
 }
@@ -5,7 +5,10 @@ use std::fmt;
 pub struct Position {
 	/// Line in the source code file.
 	pub line: usize,
-	/// Column in the [line](Self::line) in the source code file.
+	/// Column in the [line](Self::line) in the source code file, counted in chars.
+	///
+	/// See [`Source::utf16_column`](crate::source::Source::utf16_column) and
+	/// [`Source::grapheme_column`](crate::source::Source::grapheme_column) for other column units.
 	pub column: usize,
 	/// Byte offset from the start of the source code file.
 	pub offset: usize,
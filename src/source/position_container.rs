@@ -42,7 +42,7 @@ mod tests {
 	use std::sync::Arc;
 
 	use super::*;
-	use crate::source::{position_range::PositionRange, Source};
+	use crate::source::{Source, Span};
 
 	/// Tests that a [`PositionContainer`] can be dereferences to its inner value.
 	///
@@ -57,7 +57,7 @@ mod tests {
 			Number(1.0),
 			SourcePositionRange {
 				source: Arc::new(Source::new("file.name".to_owned(), "content".to_owned())),
-				position: PositionRange::default(),
+				span: Span::default(),
 			},
 		);
 
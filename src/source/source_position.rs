@@ -1,33 +1,77 @@
 use std::{fmt, sync::Arc};
 
-use crate::source::{position_range::PositionRange, Source};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::source::{position_range::PositionRange, Source, Span};
 
 /// Position in the source code ranging from start to end (both inclusive).
+///
+/// Only the [`Span`] is stored. Lines and columns are resolved on demand via [`Self::position`], since they are only
+/// needed for diagnostics.
 #[derive(PartialEq, Eq, Hash, Clone, Debug)]
 pub struct SourcePositionRange {
 	/// Source code name and text.
 	pub source: Arc<Source>,
-	/// Position range in the [source code](Self::source).
-	pub position: PositionRange,
+	/// Byte range in the [source code](Self::source).
+	pub span: Span,
 }
 
 impl SourcePositionRange {
+	/// Returns the line and column of the first and last char of this range.
+	pub fn position(&self) -> PositionRange {
+		self.source.position_range(self.span)
+	}
+
 	/// Returns the lines of the source code that this position range spans.
 	pub fn get_affected_lines(&self) -> String {
-		let source_string = self.source.text.iter().collect::<String>();
-		let lines: Vec<&str> = source_string.lines().collect();
-		lines[self.position.start.line - 1..=self.position.end.line - 1].join("\n")
+		let position = self.position();
+		let lines: Vec<&str> = (position.start.line..=position.end.line).map(|line| self.source.line(line)).collect();
+		lines.join("\n")
+	}
+
+	/// Returns the lines of the source code that this position range spans, each followed by a line underlining the
+	/// spanned code with `^`.
+	///
+	/// Leading whitespace of the lines is removed. Columns are counted in grapheme clusters, so that the underline
+	/// lines up with the code in the terminal.
+	pub fn highlight(&self) -> String {
+		let position = self.position();
+		let mut output = String::new();
+		for line_number in position.start.line..=position.end.line {
+			let line_with_whitespaces = self.source.line(line_number);
+			let line = line_with_whitespaces.trim_start();
+			// Whitespace is ASCII, so bytes equal grapheme clusters
+			let spaces_removed = line_with_whitespaces.len() - line.len();
+			// Lines in between are underlined from their first non-whitespace char to their end
+			let start_column = match line_number == position.start.line {
+				true => self.source.grapheme_column(position.start),
+				false => spaces_removed + 1,
+			};
+			let end_column = match line_number == position.end.line {
+				true => self.source.grapheme_column(position.end),
+				false => line_with_whitespaces.graphemes(true).count(),
+			};
+
+			if line_number != position.start.line {
+				output.push('\n');
+			}
+			output.push_str(line);
+			output.push('\n');
+			output.push_str(&" ".repeat(start_column.saturating_sub(1 + spaces_removed)));
+			output.push_str(&"^".repeat((end_column + 1).saturating_sub(start_column)));
+		}
+		output
 	}
 
 	/// Returns the code that this position range spans.
 	pub fn get_affected_code(&self) -> String {
-		self.source.text[self.position.start.offset..=self.position.end.offset].iter().collect::<String>()
+		self.source.text[self.span.range()].to_owned()
 	}
 }
 
 impl fmt::Display for SourcePositionRange {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-		write!(f, "{}:{}", self.source.name, self.position.start)
+		write!(f, "{}:{}", self.source.name, self.source.position(self.span.range().start))
 	}
 }
 
@@ -39,12 +83,40 @@ mod tests {
 	#[test]
 	fn test_display() {
 		let position = SourcePositionRange {
-			source: Arc::new(Source::new("file.name".to_owned(), "text...".to_owned())),
-			position: PositionRange {
-				start: Position { line: 42, column: 5, offset: 1337 },
-				end: Position { line: 43, column: 1, offset: 1340 },
-			},
+			source: Arc::new(Source::new("file.name".to_owned(), "text\n\tvar x".to_owned())),
+			span: Span::new(10, 11),
 		};
-		assert_eq!(position.to_string(), "file.name:42:5")
+		assert_eq!(position.to_string(), "file.name:2:6")
+	}
+
+	#[test]
+	fn test_position() {
+		let position = SourcePositionRange {
+			source: Arc::new(Source::new("file.name".to_owned(), "text\n\tvar größe".to_owned())),
+			span: Span::new(10, 17),
+		};
+		assert_eq!(
+			position.position(),
+			PositionRange {
+				start: Position { line: 2, column: 6, offset: 10 },
+				end: Position { line: 2, column: 10, offset: 16 },
+			}
+		);
+		assert_eq!(position.get_affected_code(), "größe");
+	}
+
+	#[test]
+	fn test_highlight() {
+		let source = Arc::new(Source::new("file.name".to_owned(), "text\n\tvar größe".to_owned()));
+		let position = SourcePositionRange { source, span: Span::new(10, 17) };
+		assert_eq!(position.highlight(), "var größe\n    ^^^^^");
+	}
+
+	/// Tests that each line of a range spanning several lines is underlined separately.
+	#[test]
+	fn test_highlight_multiple_lines() {
+		let source = Arc::new(Source::new("file.name".to_owned(), "\treturn 1.5 +\n\n\t\t2.5\n".to_owned()));
+		let position = SourcePositionRange { source, span: Span::new(8, 20) };
+		assert_eq!(position.highlight(), "return 1.5 +\n       ^^^^^\n\n\n2.5\n^^^");
 	}
 }
@@ -0,0 +1,40 @@
+use std::ops::Range;
+
+/// Byte range in the source code, from [`start`](Self::start) (inclusive) to [`end`](Self::end) (exclusive).
+///
+/// Unlike a [`SourcePositionRange`](crate::source::SourcePositionRange), a span neither references its
+/// [`Source`](crate::source::Source) nor stores lines and columns, which makes it cheap to copy. Use
+/// [`Source::locate`](crate::source::Source::locate) to convert it.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, Default)]
+pub struct Span {
+	/// Byte offset of the first byte.
+	pub start: u32,
+	/// Byte offset after the last byte.
+	pub end: u32,
+}
+
+impl Span {
+	/// Creates a [`Span`] from byte offsets.
+	///
+	/// # Panics
+	///
+	/// Panics if an offset does not fit into a `u32`, i.e. for source files larger than 4 GiB.
+	pub fn new(start: usize, end: usize) -> Self {
+		let to_u32 = |offset: usize| u32::try_from(offset).expect("Source file too large");
+		Self { start: to_u32(start), end: to_u32(end) }
+	}
+
+	/// Returns the byte range of this span, e.g. for slicing the source code.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use fortytwolang::source::Span;
+	///
+	/// let text = "var x";
+	/// assert_eq!(&text[Span::new(4, 5).range()], "x");
+	/// ```
+	pub fn range(self) -> Range<usize> {
+		self.start as usize..self.end as usize
+	}
+}
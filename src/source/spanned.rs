@@ -0,0 +1,33 @@
+use std::{ops::Deref, sync::Arc};
+
+use crate::source::{PositionContainer, Source, Span};
+
+/// Wrapper for values inside source code with a compact [`Span`].
+///
+/// The lightweight counterpart of [`PositionContainer`], e.g. used for [`Token`](crate::token::Token)s.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Spanned<T> {
+	/// Where the [`value`](Self::value) is located in the source code.
+	pub span: Span,
+	/// The value that is wrapped with [span information](Self::span).
+	pub value: T,
+}
+
+impl<T> Spanned<T> {
+	pub fn new(value: T, span: Span) -> Self {
+		Self { value, span }
+	}
+
+	/// Converts this into a [`PositionContainer`] with line and column information.
+	pub fn locate(self, source: &Arc<Source>) -> PositionContainer<T> {
+		PositionContainer::new(self.value, source.locate(self.span))
+	}
+}
+
+impl<T> Deref for Spanned<T> {
+	type Target = T;
+
+	fn deref(&self) -> &Self::Target {
+		&self.value
+	}
+}
@@ -2,10 +2,15 @@
 
 use std::fmt;
 
-use crate::source::PositionContainer;
+use crate::source::{PositionContainer, Spanned};
 
-/// A [`TokenKind`] with its position in the source code.
-pub type Token = PositionContainer<TokenKind>;
+/// A [`TokenKind`] with its [span](crate::source::Span) in the source code.
+pub type Token = Spanned<TokenKind>;
+
+/// A [`TokenKind`] located in the source code, as reported in [parser errors](crate::parser::Error).
+///
+/// Created from a [`Token`] via [`Spanned::locate`].
+pub type LocatedToken = PositionContainer<TokenKind>;
 
 /// The words produced by the [`Lexer`](crate::lexer::Lexer), which are then further processed by the [`Parser`](crate::parser::Parser).
 #[derive(Debug, Clone, PartialEq)]
@@ -16,14 +21,22 @@ pub enum TokenKind {
 	Extern,
 	/// Function, variable name or data type.
 	Identifier(String),
-	/// Floating point number.
-	Float(f64),
-	/// Integer number.
-	Int(i64),
-	/// Comment (Possible a doc comment)
+	/// Floating point number, optionally with a type suffix like `1.5f32`.
+	Float(f64, Option<NumberSuffix>),
+	/// Integer number, optionally with a type suffix like `42u8`.
+	///
+	/// Integer literals are never negative, since a leading `-` is lexed as [`TokenKind::Minus`].
+	Int(u64, Option<NumberSuffix>),
+	/// Comment, starting with `#`, or block comment enclosed by `#[` and `]#`.
+	///
+	/// Contains the text after the `#` of a line comment, or the whole block comment including `#[` and `]#`.
 	Comment(String),
+	/// Doc comment, starting with `##`, which documents the following function, struct or field.
+	DocComment(String),
 	/// String literal, enclosed by `"`.
 	StringLiteral(String),
+	/// Char literal, enclosed by `'`.
+	CharLiteral(char),
 	/// `+`
 	Plus,
 	/// `*`
@@ -34,6 +47,10 @@ pub enum TokenKind {
 	Less,
 	/// `>`
 	Greater,
+	/// `<=`
+	LessEqual,
+	/// `>=`
+	GreaterEqual,
 	/// `(`
 	OpeningParentheses,
 	/// `)`
@@ -56,22 +73,56 @@ pub enum TokenKind {
 	Slash,
 	/// `=`
 	Equal,
+	/// `==`
+	DoubleEqual,
 	/// `=/=`
 	NotEqual,
-	/// Bitwise OR
+	/// Bitwise OR `bitor`
 	BitOr,
-	/// Bitwise AND
+	/// Bitwise AND `bitand`
 	BitAnd,
-	/// Modulus %
+	/// Bitwise XOR `bitxor`
+	BitXor,
+	/// Bitwise NOT `bitnot`
+	BitNot,
+	/// Shift left `shl`
+	ShiftLeft,
+	/// Shift right `shr`
+	ShiftRight,
+	/// Modulus `mod` or `%`
 	Modulus,
+	/// Logical AND
+	And,
+	/// Logical OR
+	Or,
+	/// Logical XOR
+	Xor,
+	/// Logical NOT
+	Not,
 	/// If
 	If,
 	/// Else
 	Else,
 	/// `while` loop
 	While,
+	/// `for` loop
+	For,
+	/// `in` of a `for` loop over a range
+	In,
+	/// `of` of a `for` loop over an array
+	Of,
+	/// `step` of a range in a `for` loop
+	Step,
+	/// `break` out of a loop
+	Break,
+	/// `continue` with the next iteration of a loop
+	Continue,
 	/// `.`
 	Dot,
+	/// `..`, exclusive range
+	DoubleDot,
+	/// `..=`, inclusive range
+	DoubleDotEqual,
 	/// End of line, i.e. `\n`.
 	EndOfLine,
 	/// `ptr`
@@ -82,6 +133,25 @@ pub enum TokenKind {
 	Var,
 	/// `return`
 	Return,
+	/// `arr`, array type
+	Array,
+	/// `@`, index of an array
+	At,
+	/// `new`, constructing a struct
+	New,
+	/// `ref`, pointer to a value
+	Reference,
+	/// `deref`, value of a pointer
+	Dereference,
+	/// `alloc`, allocating a value on the heap
+	Alloc,
+	/// `del`, freeing a value on the heap
+	Delete,
+	/// `nil`, null pointer
+	Nil,
+	/// Placeholder for source code that could not be lexed. The corresponding [`lexer::Error`](crate::lexer::Error)
+	/// is reported separately.
+	Error,
 }
 
 impl fmt::Display for TokenKind {
@@ -89,3 +159,86 @@ impl fmt::Display for TokenKind {
 		<Self as fmt::Debug>::fmt(self, f)
 	}
 }
+
+/// Type suffix of a number literal, like `u8` in `42u8`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NumberSuffix {
+	I8,
+	I16,
+	I32,
+	I64,
+	U8,
+	U16,
+	U32,
+	U64,
+	F32,
+	F64,
+}
+
+impl NumberSuffix {
+	/// Checks whether this suffix denotes a floating point type.
+	pub fn is_float(&self) -> bool {
+		matches!(self, NumberSuffix::F32 | NumberSuffix::F64)
+	}
+
+	/// Checks whether this suffix denotes an unsigned integer type.
+	pub fn is_unsigned(&self) -> bool {
+		matches!(self, NumberSuffix::U8 | NumberSuffix::U16 | NumberSuffix::U32 | NumberSuffix::U64)
+	}
+
+	/// Returns the biggest integer literal that fits into the type denoted by this suffix.
+	///
+	/// The sign is a separate token, so for signed types this is the magnitude of their minimum, e.g. `128` for `i8`.
+	/// This way, the minimum can be written as negated literal like `-128i8`.
+	pub fn max_int(&self) -> u64 {
+		match self {
+			NumberSuffix::I8 => i8::MIN.unsigned_abs().into(),
+			NumberSuffix::I16 => i16::MIN.unsigned_abs().into(),
+			NumberSuffix::I32 => i32::MIN.unsigned_abs().into(),
+			NumberSuffix::I64 => i64::MIN.unsigned_abs(),
+			NumberSuffix::U8 => u8::MAX as u64,
+			NumberSuffix::U16 => u16::MAX as u64,
+			NumberSuffix::U32 => u32::MAX as u64,
+			NumberSuffix::U64 | NumberSuffix::F32 | NumberSuffix::F64 => u64::MAX,
+		}
+	}
+}
+
+impl TryFrom<&str> for NumberSuffix {
+	type Error = ();
+
+	/// Converts a suffix like `u8` to a [`NumberSuffix`]. Returns `Err` if `suffix` is no valid suffix.
+	fn try_from(suffix: &str) -> Result<Self, Self::Error> {
+		match suffix {
+			"i8" => Ok(NumberSuffix::I8),
+			"i16" => Ok(NumberSuffix::I16),
+			"i32" => Ok(NumberSuffix::I32),
+			"i64" => Ok(NumberSuffix::I64),
+			"u8" => Ok(NumberSuffix::U8),
+			"u16" => Ok(NumberSuffix::U16),
+			"u32" => Ok(NumberSuffix::U32),
+			"u64" => Ok(NumberSuffix::U64),
+			"f32" => Ok(NumberSuffix::F32),
+			"f64" => Ok(NumberSuffix::F64),
+			_ => Err(()),
+		}
+	}
+}
+
+impl fmt::Display for NumberSuffix {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let suffix = match self {
+			NumberSuffix::I8 => "i8",
+			NumberSuffix::I16 => "i16",
+			NumberSuffix::I32 => "i32",
+			NumberSuffix::I64 => "i64",
+			NumberSuffix::U8 => "u8",
+			NumberSuffix::U16 => "u16",
+			NumberSuffix::U32 => "u32",
+			NumberSuffix::U64 => "u64",
+			NumberSuffix::F32 => "f32",
+			NumberSuffix::F64 => "f64",
+		};
+		write!(f, "{}", suffix)
+	}
+}